@@ -0,0 +1,265 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::lyrics::SongDescriptor;
+use crate::word_count::WordCounts;
+
+/// How much a word's share of all the words used changes from one set of songs to the other.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct WordDelta {
+    pub word: String,
+    /// The share of the words in the left set that are this word.
+    pub left: f64,
+    /// The share of the words in the right set that are this word.
+    pub right: f64,
+}
+
+impl WordDelta {
+    /// How much more (or, if negative, less) the right set uses the word.
+    pub fn delta(&self) -> f64 {
+        self.right - self.left
+    }
+}
+
+/// How the vocabularies of two sets of songs compare.
+///
+/// Similarities are `0` (rather than `NaN`) when either set has no words.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct VocabularyComparison {
+    /// The number of distinct words both sets use.
+    pub shared_word_count: usize,
+    /// The number of distinct words only the left set uses.
+    pub left_only_word_count: usize,
+    /// The number of distinct words only the right set uses.
+    pub right_only_word_count: usize,
+    /// The most used words both sets use, by how often they're used in both.
+    pub shared_words: Vec<(String, i32)>,
+    /// The most used words only the left set uses.
+    pub left_only_words: Vec<(String, i32)>,
+    /// The most used words only the right set uses.
+    pub right_only_words: Vec<(String, i32)>,
+    /// The words whose share of each set differs the most, biggest difference first.
+    pub deltas: Vec<WordDelta>,
+    /// The share of the words either set uses that both sets use.
+    pub jaccard_similarity: f64,
+    /// The cosine similarity of how often each set uses each word.
+    pub cosine_similarity: f64,
+}
+
+/// How alike the words of a song from each set are.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SongSimilarity<'a> {
+    pub left: &'a SongDescriptor,
+    pub right: &'a SongDescriptor,
+    /// The [cosine_similarity] of the two songs' word counts.
+    pub similarity: f64,
+}
+
+/// Compares the aggregated word counts of two sets of songs, listing (up to) [limit]
+/// words of each kind; ties are broken alphabetically.
+pub fn compare_word_counts(left: &WordCounts, right: &WordCounts, limit: usize) -> VocabularyComparison {
+    let mut shared_words = vec![];
+    let mut left_only_words = vec![];
+    let mut right_only_words = vec![];
+
+    for (word, count) in left {
+        match right.get(word) {
+            Some(right_count) => shared_words.push((word.clone(), count + right_count)),
+            None => left_only_words.push((word.clone(), *count))
+        }
+    }
+
+    for (word, count) in right {
+        if !left.contains_key(word) {
+            right_only_words.push((word.clone(), *count));
+        }
+    }
+
+    let shared_word_count = shared_words.len();
+    let left_only_word_count = left_only_words.len();
+    let right_only_word_count = right_only_words.len();
+
+    VocabularyComparison {
+        shared_word_count,
+        left_only_word_count,
+        right_only_word_count,
+        shared_words: most_used(shared_words, limit),
+        left_only_words: most_used(left_only_words, limit),
+        right_only_words: most_used(right_only_words, limit),
+        deltas: word_deltas(left, right, limit),
+        jaccard_similarity: jaccard_similarity(left, right),
+        cosine_similarity: cosine_similarity(left, right),
+    }
+}
+
+/// The share of the distinct words in either of [left] and [right] that are in both.
+pub fn jaccard_similarity(left: &WordCounts, right: &WordCounts) -> f64 {
+    let words = left.keys().chain(right.keys()).collect::<HashSet<_>>();
+    let shared = left.keys().filter(|word| right.contains_key(*word)).count();
+
+    match words.len() {
+        0 => 0f64,
+        total => shared as f64 / total as f64
+    }
+}
+
+/// The cosine of the angle between [left] and [right] as vectors of word counts:
+/// `1` if they use the same words in the same proportions, `0` if they share none.
+pub fn cosine_similarity(left: &WordCounts, right: &WordCounts) -> f64 {
+    let dot = left.iter()
+        .filter_map(|(word, count)| right.get(word).map(|right_count| *count as f64 * *right_count as f64))
+        .sum::<f64>();
+
+    let norm = |counts: &WordCounts| counts.values().map(|count| (*count as f64).powi(2)).sum::<f64>().sqrt();
+
+    match norm(left) * norm(right) {
+        0f64 => 0f64,
+        norms => dot / norms
+    }
+}
+
+/// The (up to) [limit] pairs of a song from [left] and a song from [right] whose
+/// words are most alike, most similar first; ties are broken by the artists and
+/// names of the songs. A song is never paired with itself and pairs that share no
+/// words are left out.
+pub fn most_similar_songs<'a>(
+    left: &[(&'a SongDescriptor, WordCounts)],
+    right: &[(&'a SongDescriptor, WordCounts)],
+    limit: usize) -> Vec<SongSimilarity<'a>> {
+    let mut pairs = left.iter()
+        .flat_map(|(left, left_counts)| right.iter()
+            .filter(move |(right, _)| left != right)
+            .map(move |(right, right_counts)| SongSimilarity {
+                left,
+                right,
+                similarity: cosine_similarity(left_counts, right_counts),
+            }))
+        .filter(|pair| pair.similarity > 0f64)
+        .collect::<Vec<_>>();
+
+    let songs = |pair: &SongSimilarity<'a>| -> (&'a str, &'a str, &'a str, &'a str) {
+        (&pair.left.artist, &pair.left.name, &pair.right.artist, &pair.right.name)
+    };
+
+    pairs.sort_by(|a, b| b.similarity.partial_cmp(&a.similarity).unwrap().then_with(|| songs(a).cmp(&songs(b))));
+    pairs.truncate(limit);
+
+    pairs
+}
+
+/// The (up to) [limit] most used of [words], most used first.
+fn most_used(mut words: Vec<(String, i32)>, limit: usize) -> Vec<(String, i32)> {
+    words.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    words.truncate(limit);
+
+    words
+}
+
+/// The (up to) [limit] words whose share of [left] and [right] differ the most.
+fn word_deltas(left: &WordCounts, right: &WordCounts, limit: usize) -> Vec<WordDelta> {
+    let (left_total, right_total) = (left.values().sum::<i32>(), right.values().sum::<i32>());
+
+    let share = |counts: &WordCounts, total: i32, word: &String| {
+        match total {
+            0 => 0f64,
+            total => *counts.get(word).unwrap_or(&0) as f64 / total as f64
+        }
+    };
+
+    let words = left.keys().chain(right.keys()).collect::<HashSet<_>>();
+
+    let mut deltas = words.into_iter()
+        .map(|word| WordDelta {
+            word: word.clone(),
+            left: share(left, left_total, word),
+            right: share(right, right_total, word),
+        })
+        .collect::<Vec<_>>();
+
+    deltas.sort_by(|a, b| b.delta().abs().partial_cmp(&a.delta().abs()).unwrap().then_with(|| a.word.cmp(&b.word)));
+    deltas.truncate(limit);
+
+    deltas
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::lyrics::make_song;
+    use crate::utils::*;
+
+    #[test]
+    fn compare_word_counts_splits_vocabulary() {
+        let left = stringify_map_keys(&hashmap!{ "fire" => 3, "go" => 1, "house" => 2 });
+        let right = stringify_map_keys(&hashmap!{ "gas" => 2, "go" => 2 });
+
+        let comparison = compare_word_counts(&left, &right, 1);
+
+        assert_eq!(comparison.shared_word_count, 1);
+        assert_eq!(comparison.left_only_word_count, 2);
+        assert_eq!(comparison.right_only_word_count, 1);
+        assert_eq!(comparison.shared_words, vec![("go".to_string(), 3)]);
+        assert_eq!(comparison.left_only_words, vec![("fire".to_string(), 3)]);
+        assert_eq!(comparison.right_only_words, vec![("gas".to_string(), 2)]);
+        assert_eq!(comparison.jaccard_similarity, 0.25);
+    }
+
+    #[test]
+    fn word_deltas_compare_shares_of_each_set() {
+        let left = stringify_map_keys(&hashmap!{ "fire" => 3, "go" => 1 });
+        let right = stringify_map_keys(&hashmap!{ "fire" => 1, "go" => 1 });
+
+        let deltas = compare_word_counts(&left, &right, 10).deltas;
+
+        assert_eq!(deltas, vec![
+            WordDelta { word: "fire".to_string(), left: 0.75, right: 0.5 },
+            WordDelta { word: "go".to_string(), left: 0.25, right: 0.5 },
+        ]);
+        assert_eq!(deltas[0].delta(), -0.25);
+    }
+
+    #[test]
+    fn similarities_handle_empty_and_identical_counts() {
+        let counts = stringify_map_keys(&hashmap!{ "fire" => 3, "go" => 1 });
+        let scaled = stringify_map_keys(&hashmap!{ "fire" => 6, "go" => 2 });
+
+        assert!((cosine_similarity(&counts, &scaled) - 1f64).abs() < 1e-9);
+        assert_eq!(jaccard_similarity(&counts, &scaled), 1f64);
+        assert_eq!(cosine_similarity(&counts, &WordCounts::new()), 0f64);
+        assert_eq!(jaccard_similarity(&WordCounts::new(), &WordCounts::new()), 0f64);
+    }
+
+    #[test]
+    fn most_similar_songs_ranks_pairs_across_sets() {
+        let fire = make_song("House of Fire", "Dave Rodgers");
+        let deja_vu = make_song("Deja Vu", "Dave Rodgers");
+        let gas = make_song("Gas Gas Gas", "Manuel");
+
+        let left = vec![
+            (&fire, stringify_map_keys(&hashmap!{ "fire" => 2, "go" => 1 })),
+            (&deja_vu, stringify_map_keys(&hashmap!{ "deja" => 2, "vu" => 2 })),
+        ];
+        let right = vec![
+            (&gas, stringify_map_keys(&hashmap!{ "gas" => 3, "go" => 1 })),
+            (&fire, stringify_map_keys(&hashmap!{ "fire" => 2, "go" => 1 })),
+        ];
+
+        let pairs = most_similar_songs(&left, &right, 5);
+
+        assert_eq!(pairs.len(), 1);
+        assert_eq!((pairs[0].left, pairs[0].right), (&fire, &gas));
+    }
+
+    #[test]
+    fn most_similar_songs_breaks_ties_by_song() {
+        let night = make_song("Night of Fire", "Niko");
+        let house = make_song("House of Fire", "Dave Rodgers");
+        let gas = make_song("Gas Gas Gas", "Manuel");
+        let fire = || stringify_map_keys(&hashmap!{ "fire" => 1 });
+
+        let pairs = most_similar_songs(&[(&night, fire()), (&house, fire())], &[(&gas, fire())], 5);
+
+        assert_eq!(pairs.iter().map(|pair| pair.left).collect::<Vec<_>>(), vec![&house, &night]);
+    }
+}
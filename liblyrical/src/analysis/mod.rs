@@ -0,0 +1,11 @@
+mod compare;
+mod profanity;
+mod richness;
+mod sentiment;
+mod tfidf;
+
+pub use compare::*;
+pub use profanity::*;
+pub use richness::*;
+pub use sentiment::*;
+pub use tfidf::*;
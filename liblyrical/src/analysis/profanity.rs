@@ -0,0 +1,216 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Read;
+
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::word_count::WordCounts;
+
+/// How explicit a song is.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Explicitness {
+    /// Whether the song uses at least as many profane words as the filter's threshold.
+    pub explicit: bool,
+    /// How many times profane words are used.
+    pub profane_words: i32,
+    /// How many different profane words are used.
+    pub unique_profane_words: usize,
+    /// Profane words per 100 words.
+    pub score: f64,
+    /// How many times each profane word is used.
+    pub counts: WordCounts,
+}
+
+/// Finds and masks profane words.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProfanityFilter {
+    words: HashSet<String>,
+    threshold: i32,
+}
+
+impl ProfanityFilter {
+    /// Makes a [ProfanityFilter] using the built-in English word list that considers
+    /// any song with a profane word explicit.
+    pub fn new() -> Self {
+        ProfanityFilter::from_list(include_str!("../../data/profanity/en.txt"))
+    }
+
+    /// Makes a [ProfanityFilter] that only knows about [words].
+    pub fn from_words<I: IntoIterator<Item = S>, S: AsRef<str>>(words: I) -> Self {
+        ProfanityFilter {
+            words: words.into_iter()
+                .map(|word| word.as_ref().trim().to_lowercase())
+                .filter(|word| !word.is_empty())
+                .collect(),
+            threshold: 1,
+        }
+    }
+
+    /// Makes a [ProfanityFilter] from a newline-separated list; lines starting with `#`
+    /// are treated as comments.
+    pub fn from_list(list: &str) -> Self {
+        ProfanityFilter::from_words(list.lines().filter(|line| !line.trim_start().starts_with('#')))
+    }
+
+    /// Reads a newline-separated list of profane words from the file at [path].
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let mut list = String::new();
+
+        File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut list))
+            .map_err(|err| format!("Failed to read profane words from {}: {}", path, err))?;
+
+        Ok(ProfanityFilter::from_list(&list))
+    }
+
+    /// Sets how many profane words a song needs to use to be considered explicit.
+    pub fn with_threshold(mut self, threshold: i32) -> Self {
+        self.threshold = threshold;
+
+        self
+    }
+
+    pub fn extend(&mut self, other: ProfanityFilter) {
+        self.words.extend(other.words);
+    }
+
+    pub fn is_profane(&self, word: &str) -> bool {
+        self.words.contains(&word.to_lowercase())
+    }
+
+    /// Rates how explicit a song is from its [word_counts] (as returned by
+    /// [crate::word_count::count_words]).
+    pub fn explicitness(&self, word_counts: &WordCounts) -> Explicitness {
+        let counts = word_counts.iter()
+            .filter(|(word, _)| self.is_profane(word))
+            .map(|(word, count)| (word.clone(), *count))
+            .collect::<WordCounts>();
+
+        let profane_words = counts.values().sum::<i32>();
+        let total_words = word_counts.values().sum::<i32>();
+
+        Explicitness {
+            explicit: profane_words > 0 && profane_words >= self.threshold,
+            profane_words,
+            unique_profane_words: counts.len(),
+            score: match total_words {
+                0 => 0.0,
+                total_words => profane_words as f64 * 100.0 / total_words as f64
+            },
+            counts,
+        }
+    }
+
+    /// Masks a single word if it's profane ("shit" => "s***").
+    pub fn mask_word(&self, word: &str) -> String {
+        match self.is_profane(word) {
+            true => mask(word),
+            false => word.to_string()
+        }
+    }
+
+    /// Masks every profane word in [text], leaving everything else untouched.
+    pub fn mask(&self, text: &str) -> String {
+        let segments = text.split_word_bounds().collect::<Vec<_>>();
+
+        segments.iter()
+            .enumerate()
+            .map(|(i, segment)| {
+                // Words with dropped g's ("fuckin'") end in a separate apostrophe segment.
+                let elided = segments.get(i + 1) == Some(&"'") && self.is_profane(&format!("{}'", segment));
+
+                match elided || self.is_profane(segment) {
+                    true => mask(segment),
+                    false => segment.to_string()
+                }
+            })
+            .collect()
+    }
+
+    /// Masks the profane words in [word_counts]; keys can be words or phrases.
+    ///
+    /// Words that mask to the same key ("damn" and "dick" both become "d***") have
+    /// their counts added together.
+    pub fn mask_word_counts(&self, word_counts: WordCounts) -> WordCounts {
+        word_counts.into_iter()
+            .fold(WordCounts::new(), |mut acc, (word, count)| {
+                *acc.entry(self.mask(&word)).or_insert(0) += count;
+
+                acc
+            })
+    }
+}
+
+impl Default for ProfanityFilter {
+    fn default() -> Self {
+        ProfanityFilter::new()
+    }
+}
+
+fn mask(word: &str) -> String {
+    word.chars()
+        .enumerate()
+        .map(|(i, c)| match i {
+            0 => c,
+            _ => '*'
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::*;
+    use crate::word_count::count_words;
+
+    #[test]
+    fn explicitness_counts_profane_words() {
+        let word_counts = count_words("Damn, this shit is on fire, holy shit".to_string());
+        let explicitness = ProfanityFilter::new().explicitness(&word_counts);
+
+        assert!(explicitness.explicit);
+        assert_eq!(explicitness.profane_words, 3);
+        assert_eq!(explicitness.unique_profane_words, 2);
+        assert_eq!(explicitness.score, 37.5);
+        assert_eq!(explicitness.counts, stringify_map_keys(&hashmap!{ "damn" => 1, "shit" => 2 }));
+    }
+
+    #[test]
+    fn explicitness_respects_threshold() {
+        let word_counts = count_words("Damn, this house is on fire".to_string());
+
+        assert!(ProfanityFilter::new().explicitness(&word_counts).explicit);
+        assert!(!ProfanityFilter::new().with_threshold(2).explicitness(&word_counts).explicit);
+        assert!(!ProfanityFilter::new().explicitness(&count_words("Let me go".to_string())).explicit);
+    }
+
+    #[test]
+    fn mask_masks_profane_words_only() {
+        let filter = ProfanityFilter::new();
+
+        assert_eq!(filter.mask("Shit, I'm fuckin' done with this damn house"), "S***, I'm f*****' done with this d*** house");
+        assert_eq!(filter.mask_word("hello"), "hello");
+    }
+
+    #[test]
+    fn custom_words_extend_the_built_in_list() {
+        let mut filter = ProfanityFilter::new();
+        filter.extend(ProfanityFilter::from_list("# extra\nHeck\n"));
+
+        assert!(filter.is_profane("heck"));
+        assert!(filter.is_profane("SHIT"));
+        assert_eq!(
+            filter.mask_word_counts(stringify_map_keys(&hashmap!{ "heck" => 2, "heck yeah" => 1, "house" => 1 })),
+            stringify_map_keys(&hashmap!{ "h***" => 2, "h*** yeah" => 1, "house" => 1 }));
+    }
+
+    #[test]
+    fn mask_word_counts_adds_up_colliding_words() {
+        let filter = ProfanityFilter::new();
+
+        assert_eq!(
+            filter.mask_word_counts(stringify_map_keys(&hashmap!{ "damn" => 2, "dick" => 1, "shit" => 3, "slut" => 1, "house" => 1 })),
+            stringify_map_keys(&hashmap!{ "d***" => 3, "s***" => 4, "house" => 1 }));
+    }
+}
@@ -0,0 +1,204 @@
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+
+use flate2::Compression;
+use flate2::write::DeflateEncoder;
+use serde::{Deserialize, Serialize};
+
+use crate::word_count::{Tokenizer, UnicodeTokenizer};
+
+/// Vocabulary richness and repetition metrics for a song or a collection of songs.
+///
+/// Ratios are `0` (rather than `NaN`) when there's nothing to measure.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LyricsAnalysis {
+    /// The number of words ("tokens").
+    pub word_count: usize,
+    /// The number of distinct words ("types").
+    pub unique_word_count: usize,
+    /// Distinct words per word; higher means a more varied vocabulary.
+    pub type_token_ratio: f64,
+    /// The number of words that are used exactly once.
+    pub hapax_legomena: usize,
+    /// The number of lines that have at least one word.
+    pub line_count: usize,
+    /// Words per line.
+    pub average_line_length: f64,
+    /// Characters per word.
+    pub average_word_length: f64,
+    /// The share of lines that repeat an earlier line of the same song.
+    pub repetition_ratio: f64,
+    /// How many times the most repeated stanza (presumably the chorus) is sung; for
+    /// a collection, the most any one song sings its chorus. `0` if no stanza repeats.
+    pub chorus_repeats: usize,
+    /// Compressed size over original size; lower means more repetitive.
+    pub compression_ratio: f64,
+}
+
+/// Analyzes the lyrics of a single song.
+pub fn analyze_lyrics(src: &str) -> LyricsAnalysis {
+    analyze_collection(&[src])
+}
+
+/// Analyzes the lyrics of several songs together, as if they were one body of text;
+/// lines and stanzas only count as repeats within the same song, though.
+pub fn analyze_collection<S: AsRef<str>>(lyrics: &[S]) -> LyricsAnalysis {
+    let tokenizer = UnicodeTokenizer::default();
+
+    let songs = lyrics.iter()
+        .map(|src| stanzas(src.as_ref(), &tokenizer))
+        .collect::<Vec<_>>();
+
+    let lines = songs.iter()
+        .flat_map(|stanzas| stanzas.iter().flatten())
+        .collect::<Vec<_>>();
+
+    let words = lines.iter()
+        .flat_map(|line| line.iter())
+        .collect::<Vec<_>>();
+
+    let word_counts = words.iter()
+        .fold(HashMap::new(), |mut acc: HashMap<&String, usize>, word| {
+            *acc.entry(word).or_insert(0) += 1;
+
+            acc
+        });
+
+    let repeated_lines = songs.iter()
+        .map(|stanzas| {
+            let mut seen = HashSet::new();
+
+            stanzas.iter()
+                .flatten()
+                .filter(|line| !seen.insert(*line))
+                .count()
+        })
+        .sum::<usize>();
+
+    let text = lyrics.iter()
+        .map(|src| src.as_ref())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    LyricsAnalysis {
+        word_count: words.len(),
+        unique_word_count: word_counts.len(),
+        type_token_ratio: ratio(word_counts.len(), words.len()),
+        hapax_legomena: word_counts.values().filter(|count| **count == 1).count(),
+        line_count: lines.len(),
+        average_line_length: ratio(words.len(), lines.len()),
+        average_word_length: ratio(words.iter().map(|word| word.chars().count()).sum(), words.len()),
+        repetition_ratio: ratio(repeated_lines, lines.len()),
+        chorus_repeats: songs.iter().map(|stanzas| chorus_repeats(stanzas)).max().unwrap_or(0),
+        compression_ratio: compression_ratio(&text),
+    }
+}
+
+/// Splits [src] into blank-line-separated stanzas of tokenized lines, so lines
+/// compare equal regardless of case and punctuation.
+fn stanzas(src: &str, tokenizer: &UnicodeTokenizer) -> Vec<Vec<Vec<String>>> {
+    src.lines()
+        .map(|line| tokenizer.tokenize(line))
+        .collect::<Vec<_>>()
+        .split(|line| line.is_empty())
+        .filter(|stanza| !stanza.is_empty())
+        .map(|stanza| stanza.to_vec())
+        .collect()
+}
+
+fn chorus_repeats(stanzas: &[Vec<Vec<String>>]) -> usize {
+    let max_repeats = stanzas.iter()
+        .fold(HashMap::new(), |mut acc, stanza| {
+            *acc.entry(stanza).or_insert(0) += 1;
+
+            acc
+        })
+        .into_values()
+        .max()
+        .unwrap_or(0);
+
+    match max_repeats {
+        0 | 1 => 0,
+        repeats => repeats
+    }
+}
+
+fn compression_ratio(text: &str) -> f64 {
+    if text.is_empty() {
+        return 0.0;
+    }
+
+    let mut encoder = DeflateEncoder::new(vec![], Compression::best());
+
+    // Writing to a Vec can't fail.
+    encoder.write_all(text.as_bytes()).unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    ratio(compressed.len(), text.len())
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    match denominator {
+        0 => 0.0,
+        denominator => numerator as f64 / denominator as f64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const LYRICS: &str = "Let me go, let me go\nI just wanna go\n\nWelcome to the house\nOf fire\n\nlet me go let me go\n\nWelcome to the house!\nof fire";
+
+    #[test]
+    fn analyze_lyrics_measures_vocabulary() {
+        let analysis = analyze_lyrics("Let me go, let me go\nI just wanna go");
+
+        assert_eq!(analysis.word_count, 10);
+        assert_eq!(analysis.unique_word_count, 6);
+        assert_eq!(analysis.type_token_ratio, 0.6);
+        assert_eq!(analysis.hapax_legomena, 3);
+        assert_eq!(analysis.line_count, 2);
+        assert_eq!(analysis.average_line_length, 5.0);
+        assert_eq!(analysis.average_word_length, 26.0 / 10.0);
+    }
+
+    #[test]
+    fn analyze_lyrics_measures_repetition() {
+        let analysis = analyze_lyrics(LYRICS);
+
+        assert_eq!(analysis.line_count, 7);
+        assert_eq!(analysis.repetition_ratio, 3.0 / 7.0);
+        assert_eq!(analysis.chorus_repeats, 2);
+        assert!(analysis.compression_ratio > 0.0 && analysis.compression_ratio < 1.0);
+    }
+
+    #[test]
+    fn analyze_lyrics_handles_empty_lyrics() {
+        assert_eq!(analyze_lyrics(""), LyricsAnalysis::default());
+    }
+
+    #[test]
+    fn analyze_collection_only_counts_repeats_within_songs() {
+        let analysis = analyze_collection(&["Let me go\n\nfire", "let me go"]);
+
+        assert_eq!(analysis.word_count, 7);
+        assert_eq!(analysis.unique_word_count, 4);
+        assert_eq!(analysis.line_count, 3);
+        assert_eq!(analysis.repetition_ratio, 0.0);
+        assert_eq!(analysis.chorus_repeats, 0);
+    }
+
+    #[test]
+    fn analyze_collection_reports_the_most_repeated_chorus() {
+        let analysis = analyze_collection(&[LYRICS, "Fire
+
+fire
+
+fire", "Let me go
+
+let me go"]);
+
+        assert_eq!(analysis.chorus_repeats, 3);
+    }
+}
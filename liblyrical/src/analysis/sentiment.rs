@@ -0,0 +1,326 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::word_count::{Tokenizer, UnicodeTokenizer};
+
+/// Words that flip the valence of the words that follow them ("not happy").
+const NEGATIONS: [&str; 22] = [
+    "not", "no", "never", "nothing", "nobody", "nowhere", "neither", "nor", "cannot", "ain't",
+    "don't", "doesn't", "didn't", "won't", "wouldn't", "can't", "couldn't", "shouldn't", "isn't",
+    "aren't", "wasn't", "weren't",
+];
+
+/// Words that make the word that follows them stronger ("so happy").
+const BOOSTERS: [&str; 10] = [
+    "so", "very", "really", "too", "such", "totally", "completely", "absolutely", "extremely", "forever",
+];
+
+/// Words that make the word that follows them weaker ("kinda happy").
+const DAMPENERS: [&str; 6] = ["barely", "hardly", "kinda", "slightly", "somewhat", "little"];
+
+/// How far back (in words) a negation still applies.
+const NEGATION_WINDOW: usize = 3;
+/// How much a negation scales a word's valence by.
+const NEGATION_SCALAR: f64 = -0.74;
+/// How much a booster or dampener adds to or takes from a word's valence.
+const BOOSTER_INCREMENT: f64 = 0.293;
+/// Normalizes the summed valence into (-1, 1); roughly the highest expected sum.
+const NORMALIZATION_ALPHA: f64 = 15.0;
+
+/// NRC-style emotion categories.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[serde(rename_all = "lowercase")]
+pub enum Emotion {
+    Anger,
+    Anticipation,
+    Disgust,
+    Fear,
+    Joy,
+    Sadness,
+    Surprise,
+    Trust,
+}
+
+impl Emotion {
+    pub const ALL: [Emotion; 8] = [
+        Emotion::Anger,
+        Emotion::Anticipation,
+        Emotion::Disgust,
+        Emotion::Fear,
+        Emotion::Joy,
+        Emotion::Sadness,
+        Emotion::Surprise,
+        Emotion::Trust,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Emotion::Anger => "anger",
+            Emotion::Anticipation => "anticipation",
+            Emotion::Disgust => "disgust",
+            Emotion::Fear => "fear",
+            Emotion::Joy => "joy",
+            Emotion::Sadness => "sadness",
+            Emotion::Surprise => "surprise",
+            Emotion::Trust => "trust",
+        }
+    }
+}
+
+/// How a piece of text feels.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Sentiment {
+    /// Overall valence, from -1 (most negative) to 1 (most positive).
+    pub compound: f64,
+    /// The share of words that are positive.
+    pub positive: f64,
+    /// The share of words that are negative.
+    pub negative: f64,
+    /// The share of words that are neither.
+    pub neutral: f64,
+    /// How many words are associated with each emotion; emotions without any are left out.
+    pub emotions: BTreeMap<Emotion, usize>,
+}
+
+impl Sentiment {
+    /// The emotions with the most associated words, most common first.
+    pub fn dominant_emotions(&self, limit: usize) -> Vec<(Emotion, usize)> {
+        let mut emotions = self.emotions.iter()
+            .map(|(emotion, count)| (*emotion, *count))
+            .collect::<Vec<_>>();
+
+        emotions.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        emotions.truncate(limit);
+
+        emotions
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LineSentiment {
+    pub line: String,
+    pub sentiment: Sentiment,
+}
+
+/// The [Sentiment] of a song as a whole and of each of its lines.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SongSentiment {
+    pub overall: Sentiment,
+    pub lines: Vec<LineSentiment>,
+}
+
+/// Raw sums for a piece of text, which can be added together before being turned
+/// into a [Sentiment].
+#[derive(Default)]
+struct SentimentTotals {
+    valence: f64,
+    words: usize,
+    positive_words: usize,
+    negative_words: usize,
+    emotions: BTreeMap<Emotion, usize>,
+}
+
+impl SentimentTotals {
+    fn add(&mut self, other: &SentimentTotals) {
+        self.valence += other.valence;
+        self.words += other.words;
+        self.positive_words += other.positive_words;
+        self.negative_words += other.negative_words;
+
+        for (emotion, count) in &other.emotions {
+            *self.emotions.entry(*emotion).or_insert(0) += count;
+        }
+    }
+
+    fn to_sentiment(&self) -> Sentiment {
+        let share = |count: usize| match self.words {
+            0 => 0.0,
+            words => count as f64 / words as f64
+        };
+
+        Sentiment {
+            compound: self.valence / (self.valence * self.valence + NORMALIZATION_ALPHA).sqrt(),
+            positive: share(self.positive_words),
+            negative: share(self.negative_words),
+            neutral: share(self.words - self.positive_words - self.negative_words),
+            emotions: self.emotions.clone(),
+        }
+    }
+}
+
+/// Scores text using a valence lexicon (VADER-style, with negations and intensifiers)
+/// and an emotion lexicon (NRC-style).
+#[derive(Clone, Debug)]
+pub struct SentimentAnalyzer {
+    valences: HashMap<String, f64>,
+    emotions: HashMap<String, Vec<Emotion>>,
+    tokenizer: UnicodeTokenizer,
+}
+
+impl SentimentAnalyzer {
+    /// Makes a [SentimentAnalyzer] using the built-in English lexicons.
+    pub fn new() -> Self {
+        SentimentAnalyzer::from_lexicons(
+            include_str!("../../data/sentiment/valence.txt"),
+            include_str!("../../data/sentiment/emotions.txt"))
+    }
+
+    /// Makes a [SentimentAnalyzer] from a `word=valence` lexicon and a
+    /// `word=emotion,emotion` lexicon; unknown emotions are ignored.
+    pub fn from_lexicons(valences: &str, emotions: &str) -> Self {
+        let valences = parse_lexicon(valences)
+            .filter_map(|(word, valence)| Some((word, valence.parse::<f64>().ok()?)))
+            .collect();
+
+        let emotions = parse_lexicon(emotions)
+            .map(|(word, emotions)| {
+                let emotions = emotions.split(',')
+                    .filter_map(|emotion| Emotion::ALL.iter().find(|candidate| candidate.name() == emotion.trim()))
+                    .cloned()
+                    .collect();
+
+                (word, emotions)
+            })
+            .collect();
+
+        SentimentAnalyzer { valences, emotions, tokenizer: UnicodeTokenizer::default() }
+    }
+
+    /// Scores [text] as a whole.
+    pub fn score(&self, text: &str) -> Sentiment {
+        self.totals(text).to_sentiment()
+    }
+
+    /// Scores [lyrics] as a whole and line by line; lines without words are skipped.
+    pub fn analyze(&self, lyrics: &str) -> SongSentiment {
+        let mut overall = SentimentTotals::default();
+
+        let lines = lyrics.lines()
+            .map(|line| (line, self.totals(line)))
+            .filter(|(_, totals)| totals.words > 0)
+            .map(|(line, totals)| {
+                overall.add(&totals);
+
+                LineSentiment { line: line.trim().to_string(), sentiment: totals.to_sentiment() }
+            })
+            .collect();
+
+        SongSentiment { overall: overall.to_sentiment(), lines }
+    }
+
+    fn totals(&self, text: &str) -> SentimentTotals {
+        let words = self.tokenizer.tokenize(text);
+        let mut totals = SentimentTotals { words: words.len(), ..SentimentTotals::default() };
+
+        for (i, word) in words.iter().enumerate() {
+            for emotion in self.emotions.get(word).into_iter().flatten() {
+                *totals.emotions.entry(*emotion).or_insert(0) += 1;
+            }
+
+            let mut valence = match self.valences.get(word) {
+                Some(valence) => *valence,
+                None => continue
+            };
+
+            if i > 0 {
+                let intensity = match words[i - 1].as_str() {
+                    previous if BOOSTERS.contains(&previous) => BOOSTER_INCREMENT,
+                    previous if DAMPENERS.contains(&previous) => -BOOSTER_INCREMENT,
+                    _ => 0.0
+                };
+
+                valence += valence.signum() * intensity;
+            }
+
+            if words[i.saturating_sub(NEGATION_WINDOW)..i].iter().any(|previous| NEGATIONS.contains(&previous.as_str())) {
+                valence *= NEGATION_SCALAR;
+            }
+
+            totals.valence += valence;
+
+            if valence > 0.0 {
+                totals.positive_words += 1;
+            } else if valence < 0.0 {
+                totals.negative_words += 1;
+            }
+        }
+
+        totals
+    }
+}
+
+impl Default for SentimentAnalyzer {
+    fn default() -> Self {
+        SentimentAnalyzer::new()
+    }
+}
+
+/// Parses a `word=value` lexicon, skipping comments and blank lines.
+fn parse_lexicon(lexicon: &str) -> impl Iterator<Item = (String, &str)> {
+    lexicon.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut parts = line.splitn(2, '=');
+
+            Some((parts.next()?.trim().to_lowercase(), parts.next()?.trim()))
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn score_rates_positive_and_negative_text() {
+        let analyzer = SentimentAnalyzer::new();
+
+        assert!(analyzer.score("I love you, you're beautiful").compound > 0.5);
+        assert!(analyzer.score("I hate these lies, I'm so lonely").compound < -0.5);
+        assert_eq!(analyzer.score("Take me to the house").compound, 0.0);
+    }
+
+    #[test]
+    fn score_handles_negations_and_boosters() {
+        let analyzer = SentimentAnalyzer::new();
+
+        assert!(analyzer.score("I'm not happy").compound < 0.0);
+        assert!(analyzer.score("I'm so happy").compound > analyzer.score("I'm happy").compound);
+        assert!(analyzer.score("I'm kinda happy").compound < analyzer.score("I'm happy").compound);
+    }
+
+    #[test]
+    fn score_counts_word_shares_and_emotions() {
+        let sentiment = SentimentAnalyzer::new().score("happy tears tonight");
+
+        assert_eq!(sentiment.positive, 1.0 / 3.0);
+        assert_eq!(sentiment.negative, 1.0 / 3.0);
+        assert_eq!(sentiment.neutral, 1.0 / 3.0);
+        assert_eq!(sentiment.emotions, btreemap!{
+            Emotion::Anticipation => 2,
+            Emotion::Joy => 1,
+            Emotion::Sadness => 1,
+            Emotion::Trust => 1,
+        });
+        assert_eq!(sentiment.dominant_emotions(2), vec![(Emotion::Anticipation, 2), (Emotion::Joy, 1)]);
+    }
+
+    #[test]
+    fn analyze_scores_each_line() {
+        let sentiment = SentimentAnalyzer::new().analyze("I love you\n\nI hate you\n");
+
+        assert_eq!(sentiment.lines.len(), 2);
+        assert_eq!(sentiment.lines[0].line, "I love you");
+        assert!(sentiment.lines[0].sentiment.compound > 0.0);
+        assert!(sentiment.lines[1].sentiment.compound < 0.0);
+        assert_eq!(sentiment.overall, SentimentAnalyzer::new().score("I love you I hate you"));
+    }
+
+    #[test]
+    fn emotions_serialize_as_names() {
+        let sentiment = SentimentAnalyzer::new().score("joy");
+
+        assert_eq!(serde_json::to_value(&sentiment.emotions).unwrap(), serde_json::json!({ "joy": 1, "trust": 1 }));
+    }
+}
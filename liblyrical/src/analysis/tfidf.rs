@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::lyrics::{strip_featured_artists, SongDescriptor};
+use crate::word_count::{aggregate_word_counts, WordCounts};
+
+/// Scores for each word in a group of songs.
+pub type WordScores = HashMap<String, f64>;
+
+/// Built-in ways of grouping songs for [tf_idf].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GroupBy {
+    /// The artist exactly as given.
+    Artist,
+    /// The artist without any featured artists ("Beyoncé feat. JAY-Z" => "Beyoncé").
+    PrimaryArtist,
+}
+
+impl GroupBy {
+    pub const ALL: [GroupBy; 2] = [GroupBy::Artist, GroupBy::PrimaryArtist];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GroupBy::Artist => "artist",
+            GroupBy::PrimaryArtist => "primary-artist",
+        }
+    }
+
+    /// The key of the group [song] belongs to.
+    pub fn key(&self, song: &SongDescriptor) -> String {
+        match self {
+            GroupBy::Artist => song.artist.trim().to_string(),
+            GroupBy::PrimaryArtist => strip_featured_artists(&song.artist),
+        }
+    }
+}
+
+impl FromStr for GroupBy {
+    type Err = String;
+
+    fn from_str(group_by: &str) -> Result<Self, Self::Err> {
+        let group_by = group_by.trim().to_lowercase();
+
+        GroupBy::ALL.iter()
+            .find(|candidate| candidate.name() == group_by)
+            .cloned()
+            .ok_or_else(|| format!(
+                "Unknown grouping \"{}\"; expected one of: {}",
+                group_by,
+                GroupBy::ALL.iter().map(|group_by| group_by.name()).collect::<Vec<_>>().join(", ")))
+    }
+}
+
+/// Merges the [WordCounts] of songs that share the same [key] (e.g. the same artist).
+pub fn group_word_counts<'a, I, F>(word_counts: I, key: F) -> HashMap<String, WordCounts>
+where
+    I: IntoIterator<Item = (&'a SongDescriptor, WordCounts)>,
+    F: Fn(&SongDescriptor) -> String,
+{
+    word_counts.into_iter()
+        .fold(HashMap::new(), |mut acc: HashMap<String, Vec<WordCounts>>, (song, counts)| {
+            acc.entry(key(song)).or_default().push(counts);
+
+            acc
+        })
+        .into_iter()
+        .map(|(key, counts)| (key, aggregate_word_counts(counts)))
+        .collect()
+}
+
+/// Scores every word in each of [groups] by TF-IDF, treating each group as one
+/// document: how often the word is used within the group, weighted by how few of
+/// the other groups use it at all.
+///
+/// Words that every group uses score `0`, so with a single group nothing is distinctive.
+pub fn tf_idf(groups: &HashMap<String, WordCounts>) -> HashMap<String, WordScores> {
+    let num_groups = groups.len() as f64;
+
+    let document_frequencies = groups.values()
+        .flat_map(|counts| counts.keys())
+        .fold(HashMap::new(), |mut acc: HashMap<&String, usize>, word| {
+            *acc.entry(word).or_insert(0) += 1;
+
+            acc
+        });
+
+    groups.iter()
+        .map(|(key, counts)| {
+            let total = counts.values().sum::<i32>() as f64;
+
+            let scores = counts.iter()
+                .map(|(word, count)| {
+                    let term_frequency = *count as f64 / total;
+                    let inverse_document_frequency = (num_groups / document_frequencies[word] as f64).ln();
+
+                    (word.clone(), term_frequency * inverse_document_frequency)
+                })
+                .collect();
+
+            (key.clone(), scores)
+        })
+        .collect()
+}
+
+/// The (up to) [limit] highest-scoring words of each of [groups] by [tf_idf], best
+/// first; ties are broken alphabetically and words that aren't distinctive at all
+/// are left out.
+pub fn distinctive_words(groups: &HashMap<String, WordCounts>, limit: usize) -> HashMap<String, Vec<(String, f64)>> {
+    tf_idf(groups).into_iter()
+        .map(|(key, scores)| {
+            let mut words = scores.into_iter()
+                .filter(|(_, score)| *score > 0.0)
+                .collect::<Vec<_>>();
+
+            words.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap().then_with(|| a.0.cmp(&b.0)));
+            words.truncate(limit);
+
+            (key, words)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::lyrics::make_song;
+    use crate::utils::*;
+
+    #[test]
+    fn group_by_parses_names() {
+        assert_eq!("artist".parse::<GroupBy>(), Ok(GroupBy::Artist));
+        assert_eq!("Primary-Artist".parse::<GroupBy>(), Ok(GroupBy::PrimaryArtist));
+        assert!("album".parse::<GroupBy>().is_err());
+    }
+
+    #[test]
+    fn group_word_counts_merges_songs_with_the_same_key() {
+        let fire = make_song("House of Fire", "Dave Rodgers");
+        let deja_vu = make_song("Deja Vu", "Dave Rodgers feat. Ken Blast");
+        let other = make_song("Gas Gas Gas", "Manuel");
+
+        let groups = group_word_counts(
+            vec![
+                (&fire, stringify_map_keys(&hashmap!{ "fire" => 2, "go" => 1 })),
+                (&deja_vu, stringify_map_keys(&hashmap!{ "go" => 2 })),
+                (&other, stringify_map_keys(&hashmap!{ "gas" => 3 })),
+            ],
+            |song| GroupBy::PrimaryArtist.key(song));
+
+        assert_eq!(groups, hashmap!{
+            "Dave Rodgers".to_string() => stringify_map_keys(&hashmap!{ "fire" => 2, "go" => 3 }),
+            "Manuel".to_string() => stringify_map_keys(&hashmap!{ "gas" => 3 }),
+        });
+    }
+
+    #[test]
+    fn tf_idf_scores_shared_words_zero() {
+        let groups = hashmap!{
+            "a".to_string() => stringify_map_keys(&hashmap!{ "go" => 3, "fire" => 1 }),
+            "b".to_string() => stringify_map_keys(&hashmap!{ "go" => 1, "gas" => 1 }),
+        };
+
+        let scores = tf_idf(&groups);
+
+        assert_eq!(scores["a"]["go"], 0.0);
+        assert_eq!(scores["a"]["fire"], 0.25 * 2f64.ln());
+        assert_eq!(scores["b"]["gas"], 0.5 * 2f64.ln());
+    }
+
+    #[test]
+    fn distinctive_words_ranks_and_limits_words() {
+        let groups = hashmap!{
+            "a".to_string() => stringify_map_keys(&hashmap!{ "go" => 3, "fire" => 2, "house" => 2, "night" => 1 }),
+            "b".to_string() => stringify_map_keys(&hashmap!{ "go" => 1, "gas" => 1 }),
+        };
+
+        let words = distinctive_words(&groups, 2);
+        let names = |key: &str| words[key].iter().map(|(word, _)| word.as_str()).collect::<Vec<_>>();
+
+        assert_eq!(names("a"), vec!["fire", "house"]);
+        assert_eq!(names("b"), vec!["gas"]);
+    }
+}
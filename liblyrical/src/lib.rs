@@ -8,6 +8,9 @@ extern crate scraper;
 extern crate serde;
 extern crate tokio;
 
+pub mod analysis;
 pub mod lyrics;
+pub mod metrics;
+pub mod rhyme;
 pub mod utils;
 pub mod word_count;
\ No newline at end of file
@@ -1,29 +1,26 @@
 use std::cell::RefCell;
-use std::collections::HashMap;
 use std::fmt::Debug;
-use std::fs::OpenOptions;
-use std::io::{Read, Write};
 use std::rc::Rc;
 
-use serde_json;
-
-use crate::utils::stringify_map_keys;
 use super::*;
 
-type HashMapCache = HashMap<SongDescriptor, CacheEntry>;
-
-static mut CACHE: Option<Rc<RefCell<HashMapCache>>> = None;
+static mut CACHE: Option<Rc<RefCell<CacheEntries>>> = None;
 
-const CACHE_LOCATION: &'static str = "./cache/lyrics.json";
+/// Where [DevCache] keeps its entries unless told otherwise.
+pub const CACHE_LOCATION: &'static str = "./cache/lyrics.json";
 
 #[derive(Default, Builder, Debug)]
 #[builder(setter(into))]
 pub struct DevCacheOptions {
     write_eagerly: bool,
+    /// The file entries are loaded from and written back to. Every [DevCache] in a
+    /// process shares the entries loaded by the first one.
+    #[builder(default = "CACHE_LOCATION.to_string()")]
+    path: String,
 }
 
 pub struct DevCache {
-    cache: Rc<RefCell<HashMapCache>>,
+    cache: Rc<RefCell<CacheEntries>>,
     options: DevCacheOptions,
 }
 
@@ -33,7 +30,7 @@ impl DevCache {
             match &CACHE {
                 Some(cache) => cache.clone(),
                 None => {
-                    let new_cache = Self::make_cache(CACHE_LOCATION);
+                    let new_cache = Self::make_cache(&options.path);
 
                     CACHE = Some(new_cache.clone());
                     new_cache
@@ -44,35 +41,18 @@ impl DevCache {
         DevCache { cache, options }
     }
 
-    fn make_cache<'a>(path: &'a str) -> Rc<RefCell<HashMapCache>> {
-        OpenOptions::new()
-            .read(true)
-            .open(path)
-            .map(|mut file| {
-                let mut serialized = String::new();
-                file.read_to_string(&mut serialized).unwrap();
-
-                let cache = serde_json::from_str::<HashMap<String, CacheEntry>>(&serialized)
-                    .map(|cache_with_serialized_keys| 
-                        cache_with_serialized_keys
-                            .into_iter()
-                            .fold(HashMap::new(), |mut acc, (key, value)| {
-                                acc.insert(serde_json::from_str(&key).unwrap(), value);
-
-                                acc
-                            })
-                    )
-                    .unwrap();
-
-                Rc::new(RefCell::new(cache))
-            })
-            .unwrap()
+    fn make_cache<'a>(path: &'a str) -> Rc<RefCell<CacheEntries>> {
+        // Older caches are migrated to [SongKey]s here and written back in the new
+        // format the next time we write back.
+        let cache = CacheEntries::from_file(path).unwrap();
+
+        Rc::new(RefCell::new(cache))
     }
 }
 
 impl Cache for DevCache {
     fn save(&mut self, song: &SongDescriptor, entry: CacheEntry) -> Result<(), String> {
-        self.cache.borrow_mut().insert(song.clone(), entry);
+        self.cache.borrow_mut().insert(song, entry);
 
         if self.options.write_eagerly {
             self.write_back()?;
@@ -91,32 +71,18 @@ impl Cache for DevCache {
     fn write_back(&mut self) -> Result<(), String> {
         // Swap the cache out just long enough to write it to disk and swap it back.
         // We're fulfilling our childhoold dreams of becoming a magician!
-        let cache = self.cache.replace(HashMap::new());
-
-        let write_back_result = serde_json::to_string(&stringify_map_keys(&cache))
-            .map(|serialized_cache| {
-                OpenOptions::new()
-                    .read(true)
-                    .write(true)
-                    .open(CACHE_LOCATION)
-                    .map(|mut file| {
-                        println!("writing cache to disk...");
-
-                        file.write_all(serialized_cache.as_bytes())
-                            .map(|_| println!("cache written to disk!"))
-                            .unwrap()
-                    })
-                    .unwrap()
-            });
+        let cache = self.cache.replace(CacheEntries::new());
+
+        eprintln!("writing cache to disk...");
+
+        let write_back_result = cache.to_file(&self.options.path)
+            .map(|_| eprintln!("cache written to disk!"));
 
         // Replace the cache before checking if the write-back operation succeeded so
         // if we do end up panicking it will be _after_ we've restored everything.
         self.cache.replace(cache);
 
-        match &write_back_result {
-            Ok(_) => Ok(()),
-            Err(err) => Err(err.to_string())
-        }
+        write_back_result
     }
 }
 
@@ -137,12 +103,12 @@ mod test {
     #[ignore]
     // Used to populate test cache -- you probably don't need or want to run this.
     fn populate_cache() {
-        let mut cache = HashMap::new();
-        cache.insert(serde_json::to_string(&SongDescriptor { name: "foo".to_string(), artist: "bar".to_string(), uri: None }).unwrap(), CacheEntry::Success("foo bar baz".to_string()));
+        let mut cache = CacheEntries::new();
+        cache.insert(&SongDescriptor { name: "foo".to_string(), artist: "bar".to_string(), uri: None }, CacheEntry::Success("foo bar baz".to_string()));
 
-        let serialized_cache = serde_json::to_string(&cache).unwrap();
+        let serialized_cache = cache.to_json().unwrap();
 
-        File::create("./test_data/cached/test_cache_temp.json")
+        File::create("./test_data/cached/test_cache_v2.json")
             .unwrap()
             .write_all(serialized_cache.as_bytes())
             .unwrap();
@@ -157,4 +123,14 @@ mod test {
 
         assert_eq!(cache.borrow().get(&key).unwrap(), &value);
     }
+
+    #[test]
+    fn can_load_cache_keyed_by_song_key() {
+        let cache = DevCache::make_cache("./test_data/cached/test_cache_v2.json");
+
+        let key = SongDescriptor{ name: "FOO".to_string(), artist: "Bar".to_string(), uri: None };
+        let value = CacheEntry::Success("foo bar baz".to_string());
+
+        assert_eq!(cache.borrow().get(&key).unwrap(), &value);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,269 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json;
+
+use super::*;
+
+/// The version of the on-disk format written by [CacheEntries::to_json].
+const CACHE_FORMAT_VERSION: u32 = 2;
+
+/// The on-disk representation of [CacheEntries].
+#[derive(Serialize, Deserialize)]
+struct SerializedCacheEntries {
+    version: u32,
+    entries: BTreeMap<String, CacheEntry>,
+    uris: BTreeMap<String, String>,
+}
+
+/// [CacheEntry]s keyed by [SongKey], with a secondary index from uris to keys so
+/// songs that are only known by their uri still resolve to the same entry.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CacheEntries {
+    entries: HashMap<SongKey, CacheEntry>,
+    uris: HashMap<String, SongKey>,
+}
+
+impl CacheEntries {
+    pub fn new() -> Self {
+        CacheEntries::default()
+    }
+
+    pub fn insert(&mut self, song: &SongDescriptor, entry: CacheEntry) {
+        self.index_uri(song);
+        self.entries.insert(SongKey::new(song), entry);
+    }
+
+    pub fn get(&self, song: &SongDescriptor) -> Option<&CacheEntry> {
+        // Prefer the uri when we have one since it's the most precise identity.
+        let key_for_uri = song.uri.as_ref()
+            .and_then(|uri| self.uris.get(uri.as_str()));
+
+        key_for_uri
+            .and_then(|key| self.entries.get(key))
+            .or_else(|| self.entries.get(&SongKey::new(song)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The cached entries, keyed by the [SongKey] of the song they're for.
+    pub fn iter(&self) -> impl Iterator<Item = (&SongKey, &CacheEntry)> {
+        self.entries.iter()
+    }
+
+    /// Removes the entry for [song], if there is one.
+    pub fn remove(&mut self, song: &SongDescriptor) -> Option<CacheEntry> {
+        let key = song.uri.as_ref()
+            .and_then(|uri| self.uris.get(uri.as_str()))
+            .filter(|key| self.entries.contains_key(key))
+            .cloned()
+            .unwrap_or_else(|| SongKey::new(song));
+
+        let entry = self.entries.remove(&key)?;
+        self.uris.retain(|_, uri_key| *uri_key != key);
+
+        Some(entry)
+    }
+
+    /// Keeps only the entries [keep] returns true for.
+    pub fn retain<F: FnMut(&SongKey, &CacheEntry) -> bool>(&mut self, mut keep: F) {
+        self.entries.retain(|key, entry| keep(key, entry));
+
+        let entries = &self.entries;
+        self.uris.retain(|_, key| entries.contains_key(key));
+    }
+
+    /// Reads the entries in the cache file at [path]; a file that doesn't exist yet
+    /// is an empty cache.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+
+        match fs::read_to_string(path) {
+            Ok(json) => Self::from_json(&json),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(CacheEntries::new()),
+            Err(err) => Err(format!("Failed to read cache {}: {}", path.display(), err))
+        }
+    }
+
+    /// Writes the entries to the cache file at [path], creating its directory if
+    /// needed.
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        let path = path.as_ref();
+        let json = self.to_json()?;
+
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .map_err(|err| format!("Failed to create cache directory {}: {}", dir.display(), err))?;
+        }
+
+        fs::write(path, json)
+            .map_err(|err| format!("Failed to write cache {}: {}", path.display(), err))
+    }
+
+    /// Parses entries written by [CacheEntries::to_json], migrating caches written
+    /// before entries were keyed by [SongKey] (i.e. keyed by serialized
+    /// [SongDescriptor]s) along the way.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let value = serde_json::from_str::<serde_json::Value>(json)
+            .map_err(|err| format!("Failed to parse cache: {}", err))?;
+
+        match value.get("version") {
+            Some(_) => Self::from_current_format(value),
+            None => Self::from_legacy_format(value)
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        let serialized = SerializedCacheEntries {
+            version: CACHE_FORMAT_VERSION,
+            entries: self.entries.iter()
+                .map(|(key, entry)| (key.to_string(), entry.clone()))
+                .collect(),
+            uris: self.uris.iter()
+                .map(|(uri, key)| (uri.clone(), key.to_string()))
+                .collect(),
+        };
+
+        serde_json::to_string(&serialized)
+            .map_err(|err| format!("Failed to serialize cache: {}", err))
+    }
+
+    fn index_uri(&mut self, song: &SongDescriptor) {
+        if let Some(uri) = &song.uri {
+            self.uris.insert(uri.as_str().to_string(), SongKey::new(song));
+        }
+    }
+
+    fn from_current_format(value: serde_json::Value) -> Result<Self, String> {
+        let serialized = serde_json::from_value::<SerializedCacheEntries>(value)
+            .map_err(|err| format!("Failed to parse cache: {}", err))?;
+
+        if serialized.version != CACHE_FORMAT_VERSION {
+            return Err(format!("Unsupported cache version {}", serialized.version));
+        }
+
+        let entries = serialized.entries.into_iter()
+            .map(|(key, entry)| Ok((key.parse()?, entry)))
+            .collect::<Result<HashMap<SongKey, CacheEntry>, String>>()?;
+
+        let uris = serialized.uris.into_iter()
+            .map(|(uri, key)| Ok((uri, key.parse()?)))
+            .collect::<Result<HashMap<String, SongKey>, String>>()?;
+
+        Ok(CacheEntries { entries, uris })
+    }
+
+    fn from_legacy_format(value: serde_json::Value) -> Result<Self, String> {
+        let legacy = serde_json::from_value::<HashMap<String, CacheEntry>>(value)
+            .map_err(|err| format!("Failed to parse legacy cache: {}", err))?;
+
+        legacy.into_iter()
+            .try_fold(CacheEntries::new(), |mut acc, (key, entry)| {
+                let song = serde_json::from_str::<SongDescriptor>(&key)
+                    .map_err(|err| format!("Failed to parse legacy cache key {}: {}", key, err))?;
+
+                // Several legacy entries can collapse into the same key; never let a
+                // failure clobber lyrics we already found.
+                let keep_existing = matches!(
+                    (acc.get(&song), &entry),
+                    (Some(CacheEntry::Success(_)), CacheEntry::Failure(_)));
+
+                match keep_existing {
+                    true => acc.index_uri(&song),
+                    false => acc.insert(&song, entry)
+                };
+
+                Ok(acc)
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cache_entries_match_songs_regardless_of_casing() {
+        let mut entries = CacheEntries::new();
+        entries.insert(&make_song("House of Fire", "Dave Rodgers"), CacheEntry::Success("fire".to_string()));
+
+        assert_eq!(
+            entries.get(&make_song("house of fire", "DAVE RODGERS")),
+            Some(&CacheEntry::Success("fire".to_string())));
+    }
+
+    #[test]
+    fn cache_entries_match_songs_by_uri() {
+        let mut entries = CacheEntries::new();
+        entries.insert(&make_song_with_uri("House of Fire", "Dave Rodgers", "https://example.com/fire"), CacheEntry::Success("fire".to_string()));
+
+        assert_eq!(
+            entries.get(&make_song_with_uri("", "", "https://example.com/fire")),
+            Some(&CacheEntry::Success("fire".to_string())));
+    }
+
+    #[test]
+    fn cache_entries_round_trip_through_json() {
+        let mut entries = CacheEntries::new();
+        entries.insert(&make_song_with_uri("House of Fire", "Dave Rodgers", "https://example.com/fire"), CacheEntry::Success("fire".to_string()));
+        entries.insert(&make_song("foo", "bar"), CacheEntry::Failure("nope".into()));
+
+        let json = entries.to_json().unwrap();
+
+        assert_eq!(CacheEntries::from_json(&json), Ok(entries));
+    }
+
+    #[test]
+    fn cache_entries_can_remove_songs_by_key_or_uri() {
+        let mut entries = CacheEntries::new();
+        entries.insert(&make_song_with_uri("House of Fire", "Dave Rodgers", "https://example.com/fire"), CacheEntry::Success("fire".to_string()));
+        entries.insert(&make_song("foo", "bar"), CacheEntry::Failure("nope".into()));
+
+        assert_eq!(entries.remove(&make_song_with_uri("", "", "https://example.com/fire")), Some(CacheEntry::Success("fire".to_string())));
+        assert_eq!(entries.remove(&make_song("FOO", "Bar")), Some(CacheEntry::Failure("nope".into())));
+        assert_eq!(entries.remove(&make_song("foo", "bar")), None);
+        assert!(entries.is_empty());
+        assert!(entries.uris.is_empty());
+    }
+
+    #[test]
+    fn cache_entries_retain_drops_uris_of_removed_entries() {
+        let mut entries = CacheEntries::new();
+        entries.insert(&make_song("House of Fire", "Dave Rodgers"), CacheEntry::Success("fire".to_string()));
+        entries.insert(&make_song_with_uri("foo", "bar", "https://example.com/foo"), CacheEntry::Failure("nope".into()));
+
+        entries.retain(|_, entry| !matches!(entry, CacheEntry::Failure(_)));
+
+        assert_eq!(entries.len(), 1);
+        assert!(entries.uris.is_empty());
+    }
+
+    #[test]
+    fn cache_entries_treat_missing_files_as_empty() {
+        assert_eq!(CacheEntries::from_file("./test_data/cached/missing.json"), Ok(CacheEntries::new()));
+    }
+
+    #[test]
+    fn cache_entries_migrate_legacy_format() {
+        let legacy = serde_json::to_string(&hashmap!{
+            make_song("House of Fire", "Dave Rodgers").to_string() => CacheEntry::Success("fire".to_string()),
+            make_song_with_uri("house of fire", "dave rodgers", "https://example.com/fire").to_string() => CacheEntry::Failure("nope".into()),
+        }).unwrap();
+
+        let entries = CacheEntries::from_json(&legacy).unwrap();
+
+        assert_eq!(entries.entries.len(), 1);
+        assert_eq!(
+            entries.get(&make_song("House Of Fire", "Dave Rodgers")),
+            Some(&CacheEntry::Success("fire".to_string())));
+    }
+}
\ No newline at end of file
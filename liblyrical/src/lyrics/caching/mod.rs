@@ -1,16 +1,21 @@
 mod dev_cache;
+mod entries;
 
 use std::fmt::Debug;
 
 use serde::{Deserialize, Serialize};
 
 use super::*;
+use crate::metrics::Metrics;
 pub use dev_cache::*;
+pub use entries::*;
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub enum CacheEntry {
     Success(String),
-    Failure(String),
+    Failure(FetchError),
+    /// The lyrics for this song are cached under another (e.g. simplified) song.
+    Alias(SongDescriptor),
 }
 
 pub trait Cache: Debug {
@@ -24,6 +29,12 @@ pub trait Cache: Debug {
 pub struct CachingLyricsFetcherOptions {
     cache_failures: bool,
     retry_cached_failures: bool,
+    #[builder(default)]
+    metrics: Option<Metrics>,
+    /// Where to look for simplifications that produced hits so they can be cached
+    /// as aliases of the original song.
+    #[builder(default)]
+    simplifications: Option<SimplificationLog>,
 }
 
 #[derive(Debug)]
@@ -38,13 +49,45 @@ impl<T: LyricsFetcher, C: Cache> CachingLyricsFetcher<T, C> {
         CachingLyricsFetcher { cache, fallback, options }
     }
 
-    fn fetch_lyrics_using_fallback(&mut self, song: &SongDescriptor) -> Result<String, String> {
+    fn increment_counter(&self, name: &str) {
+        if let Some(metrics) = &self.options.metrics {
+            metrics.increment_counter(name, &[]);
+        }
+    }
+
+    /// Loads the entry for [song], following an alias to the entry it points to.
+    fn load_entry(&self, song: &SongDescriptor) -> Result<Option<CacheEntry>, String> {
+        match self.cache.load(song)? {
+            Some(CacheEntry::Alias(target)) => match self.cache.load(&target)? {
+                // Only follow a single alias so we can't get stuck in a cycle.
+                Some(CacheEntry::Alias(_)) => Ok(None),
+                entry => Ok(entry)
+            },
+            entry => Ok(entry)
+        }
+    }
+
+    fn fetch_lyrics_using_fallback(&mut self, song: &SongDescriptor) -> Result<String, FetchError> {
         match self.fallback.fetch_lyrics(song) {
             // We found some lyrics; save the lyrics to cache and
             // return the result.
             Ok(lyrics) => {
-                let entry = CacheEntry::Success(lyrics.clone());
-                self.cache.save(song, entry)?;
+                let simplification = self.options.simplifications
+                    .as_ref()
+                    .and_then(|log| log.take_for(song));
+
+                match simplification {
+                    // The lyrics were found under a simplified song, so cache them
+                    // there and point the original song at it (unless they share a key).
+                    Some(simplification) if SongKey::new(&simplification.simplified) != SongKey::new(song) => {
+                        self.cache.save(&simplification.simplified, CacheEntry::Success(lyrics.clone()))
+                            .map_err(FetchError::cache)?;
+                        self.cache.save(song, CacheEntry::Alias(simplification.simplified))
+                            .map_err(FetchError::cache)?;
+                    },
+                    _ => self.cache.save(song, CacheEntry::Success(lyrics.clone()))
+                        .map_err(FetchError::cache)?
+                };
 
                 Ok(lyrics)
             },
@@ -54,7 +97,7 @@ impl<T: LyricsFetcher, C: Cache> CachingLyricsFetcher<T, C> {
                 if self.options.cache_failures {
                     let entry = CacheEntry::Failure(err.clone());
 
-                    self.cache.save(song, entry)?;
+                    self.cache.save(song, entry).map_err(FetchError::cache)?;
                 }
 
                 // Return the error.
@@ -65,25 +108,46 @@ impl<T: LyricsFetcher, C: Cache> CachingLyricsFetcher<T, C> {
 }
 
 impl<T: LyricsFetcher, C: Cache> LyricsFetcher for CachingLyricsFetcher<T, C> {
-    fn fetch_lyrics(&mut self, song: &SongDescriptor) -> Result<String, String> {
+    fn fetch_lyrics(&mut self, song: &SongDescriptor) -> Result<String, FetchError> {
         // Try to load the lyrics from cache.
-        match self.cache.load(song)? {
+        let entry = self.load_entry(song)
+            .inspect_err(|_| self.increment_counter("lyrical_cache_errors_total"))
+            .map_err(FetchError::cache)?;
+
+        match entry {
             // We found a result in the cache.
-            Some(entry) => match entry {
-                CacheEntry::Success(lyrics) => Ok(lyrics),
-                CacheEntry::Failure(err) => {
-                    // Check if we should retry a cached failure.
-                    // If so, do it; otherwise, just return the err directly.
-                    match self.options.retry_cached_failures {
-                        true => self.fetch_lyrics_using_fallback(song),
-                        false => Err(err)
-                    }
-                } 
+            Some(entry) => {
+                self.increment_counter("lyrical_cache_hits_total");
+
+                match entry {
+                    CacheEntry::Success(lyrics) => Ok(lyrics),
+                    CacheEntry::Failure(err) => {
+                        self.increment_counter("lyrical_cache_cached_failures_total");
+
+                        // Check if we should retry a cached failure.
+                        // If so, do it; otherwise, just return the err directly.
+                        match self.options.retry_cached_failures {
+                            true => {
+                                self.increment_counter("lyrical_cache_retries_total");
+
+                                self.fetch_lyrics_using_fallback(song)
+                            },
+                            false => Err(err)
+                        }
+                    },
+                    // [load_entry] already resolved aliases, so treat one that's
+                    // left over like a miss.
+                    CacheEntry::Alias(_) => self.fetch_lyrics_using_fallback(song)
+                }
             },
 
             // We didn't find a result in the cache, so we'll need to use our
             // fallback fetcher.
-            None => self.fetch_lyrics_using_fallback(song)
+            None => {
+                self.increment_counter("lyrical_cache_misses_total");
+
+                self.fetch_lyrics_using_fallback(song)
+            }
         }
     }
 }
@@ -92,8 +156,108 @@ impl<T: LyricsFetcher, C: Cache> Drop for CachingLyricsFetcher<T, C> {
     fn drop(&mut self) {
         // Make sure we write the cache back on drop.
         match self.cache.write_back() {
-            Err(err) => println!("Something went wrong while writing cache on CachingLyricsFetcher drop: {}", err),
+            Err(err) => eprintln!("Something went wrong while writing cache on CachingLyricsFetcher drop: {}", err),
             _ => {}
         }
     }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct FakeCache {
+        entries: CacheEntries,
+    }
+
+    impl Cache for FakeCache {
+        fn save(&mut self, song: &SongDescriptor, entry: CacheEntry) -> Result<(), String> {
+            self.entries.insert(song, entry);
+
+            Ok(())
+        }
+
+        fn load(&self, song: &SongDescriptor) -> Result<Option<CacheEntry>, String> {
+            Ok(self.entries.get(song).cloned())
+        }
+
+        fn write_back(&mut self) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    #[derive(Debug)]
+    struct FakeLyricsFetcher {
+        result: Result<String, FetchError>,
+    }
+
+    impl LyricsFetcher for FakeLyricsFetcher {
+        fn fetch_lyrics(&mut self, _song: &SongDescriptor) -> Result<String, FetchError> {
+            self.result.clone()
+        }
+    }
+
+    #[test]
+    fn caching_lyrics_fetcher_records_cache_metrics() {
+        let metrics = Metrics::new();
+        let options = CachingLyricsFetcherOptionsBuilder::default()
+            .cache_failures(true)
+            .retry_cached_failures(true)
+            .metrics(Some(metrics.clone()))
+            .build()
+            .unwrap();
+
+        let mut fetcher = CachingLyricsFetcher::new(
+            FakeLyricsFetcher { result: Err("nope".into()) },
+            FakeCache::default(),
+            options);
+
+        let song = SongDescriptor { name: "foo".to_string(), artist: "bar".to_string(), uri: None };
+
+        // The first fetch misses and caches the failure; the second hits the
+        // cached failure and retries it.
+        fetcher.fetch_lyrics(&song).unwrap_err();
+        fetcher.fetch_lyrics(&song).unwrap_err();
+
+        assert_eq!(metrics.counter("lyrical_cache_misses_total", &[]), 1);
+        assert_eq!(metrics.counter("lyrical_cache_hits_total", &[]), 1);
+        assert_eq!(metrics.counter("lyrical_cache_cached_failures_total", &[]), 1);
+        assert_eq!(metrics.counter("lyrical_cache_retries_total", &[]), 1);
+    }
+
+    #[test]
+    fn caching_lyrics_fetcher_caches_simplified_hits_as_aliases() {
+        let log = SimplificationLog::new();
+        let options = CachingLyricsFetcherOptionsBuilder::default()
+            .cache_failures(true)
+            .retry_cached_failures(true)
+            .simplifications(Some(log.clone()))
+            .build()
+            .unwrap();
+
+        let mut fetcher = CachingLyricsFetcher::new(
+            FakeLyricsFetcher { result: Ok("la la la".to_string()) },
+            FakeCache::default(),
+            options);
+
+        let song = SongDescriptor { name: "foo - Live".to_string(), artist: "bar".to_string(), uri: None };
+        let simplified = SongDescriptor { name: "foo".to_string(), artist: "bar".to_string(), uri: None };
+
+        // Pretend the fallback had to simplify the song to find it.
+        log.record(Simplification {
+            original: song.clone(),
+            simplified: simplified.clone(),
+            strategies: vec!["strip-after-hyphen".to_string()],
+        });
+
+        assert_eq!(fetcher.fetch_lyrics(&song), Ok("la la la".to_string()));
+        assert_eq!(fetcher.cache.entries.get(&song), Some(&CacheEntry::Alias(simplified.clone())));
+        assert_eq!(fetcher.cache.entries.get(&simplified), Some(&CacheEntry::Success("la la la".to_string())));
+
+        // The alias should be followed on the next fetch without hitting the fallback.
+        fetcher.fallback.result = Err("nope".into());
+
+        assert_eq!(fetcher.fetch_lyrics(&song), Ok("la la la".to_string()));
+    }
 }
\ No newline at end of file
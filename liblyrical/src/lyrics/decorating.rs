@@ -1,31 +1,86 @@
 use super::*;
 
-pub type BeforeFn = dyn Fn(&SongDescriptor) -> ();
-pub type AfterFn = dyn Fn(&SongDescriptor, &Result<String, String>) -> ();
+pub type LyricsResult = Result<String, FetchError>;
 
+pub type BeforeFn = dyn FnMut(SongDescriptor) -> BeforeFetch + Send;
+pub type AfterFn = dyn FnMut(&SongDescriptor, LyricsResult) -> LyricsResult + Send;
+
+/// What a [LyricsMiddleware] wants to happen after its `before_fetch` hook runs.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BeforeFetch {
+    /// Keep going with the (possibly rewritten) song.
+    Continue(SongDescriptor),
+    /// Skip the rest of the stack and the wrapped fetcher and use this result instead.
+    Respond(LyricsResult),
+}
+
+/// A hook around a [LyricsFetcher] that can rewrite the song before it's fetched,
+/// short-circuit the fetch entirely, or transform the result afterwards.
+pub trait LyricsMiddleware: Debug + Send {
+    fn before_fetch(&mut self, song: SongDescriptor) -> BeforeFetch {
+        BeforeFetch::Continue(song)
+    }
+
+    fn after_fetch(&mut self, _song: &SongDescriptor, result: LyricsResult) -> LyricsResult {
+        result
+    }
+}
+
+/// A [LyricsFetcher] that runs a stack of [LyricsMiddleware]s around another fetcher.
+///
+/// `before_fetch` hooks run in the order the middlewares were added and `after_fetch`
+/// hooks run in reverse, so the first middleware added is the outermost one. If a
+/// middleware short-circuits, only the middlewares added before it see the result.
 pub struct DecoratingLyricsFetcher<T: LyricsFetcher> {
     fetcher: T,
-    before: Box<BeforeFn>,
-    after: Box<AfterFn>,
+    middlewares: Vec<Box<dyn LyricsMiddleware>>,
 }
 
 impl<T: LyricsFetcher> DecoratingLyricsFetcher<T> {
-    pub fn new(fetcher: T, before: Box<BeforeFn>, after: Box<AfterFn>) -> Self {
+    pub fn new(fetcher: T, middlewares: Vec<Box<dyn LyricsMiddleware>>) -> Self {
         DecoratingLyricsFetcher {
             fetcher,
-            before,
-            after,
+            middlewares,
         }
     }
+
+    /// Adds [middleware] as the innermost middleware of the stack.
+    pub fn with_middleware<M: LyricsMiddleware + 'static>(mut self, middleware: M) -> Self {
+        self.middlewares.push(Box::new(middleware));
+
+        self
+    }
 }
 
 impl<T: LyricsFetcher> LyricsFetcher for DecoratingLyricsFetcher<T> {
-    fn fetch_lyrics(&mut self, song: &SongDescriptor) -> Result<String, String> {
-        (self.before)(song);
+    fn fetch_lyrics(&mut self, song: &SongDescriptor) -> Result<String, FetchError> {
+        // Keep track of the song each middleware saw so we can hand it back to
+        // its after hook.
+        let mut seen_songs = vec![];
+        let mut song = song.clone();
+        let mut short_circuit = None;
 
-        let result = self.fetcher.fetch_lyrics(song);
+        for middleware in &mut self.middlewares {
+            match middleware.before_fetch(song.clone()) {
+                BeforeFetch::Continue(next_song) => {
+                    seen_songs.push(song);
+                    song = next_song;
+                },
+                BeforeFetch::Respond(result) => {
+                    short_circuit = Some(result);
+                    break;
+                }
+            }
+        }
 
-        (self.after)(song, &result);
+        let mut result = match short_circuit {
+            Some(result) => result,
+            None => self.fetcher.fetch_lyrics(&song)
+        };
+
+        for (middleware, song) in self.middlewares.iter_mut().zip(seen_songs.iter()).rev() {
+            result = middleware.after_fetch(song, result);
+        }
 
         result
     }
@@ -33,6 +88,173 @@ impl<T: LyricsFetcher> LyricsFetcher for DecoratingLyricsFetcher<T> {
 
 impl<T: LyricsFetcher> Debug for DecoratingLyricsFetcher<T> {
     fn fmt(&self, formatter: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
-        formatter.write_str("DecoratingLyricsFetcher { }")
+        formatter.debug_struct("DecoratingLyricsFetcher")
+            .field("fetcher", &self.fetcher)
+            .field("middlewares", &self.middlewares)
+            .finish()
+    }
+}
+
+/// A [LyricsMiddleware] built from closures.
+pub struct FnMiddleware {
+    before: Option<Box<BeforeFn>>,
+    after: Option<Box<AfterFn>>,
+}
+
+impl FnMiddleware {
+    pub fn new(before: Option<Box<BeforeFn>>, after: Option<Box<AfterFn>>) -> Self {
+        FnMiddleware { before, after }
+    }
+
+    pub fn before(before: Box<BeforeFn>) -> Self {
+        FnMiddleware::new(Some(before), None)
+    }
+
+    pub fn after(after: Box<AfterFn>) -> Self {
+        FnMiddleware::new(None, Some(after))
+    }
+}
+
+impl LyricsMiddleware for FnMiddleware {
+    fn before_fetch(&mut self, song: SongDescriptor) -> BeforeFetch {
+        match &mut self.before {
+            Some(before) => before(song),
+            None => BeforeFetch::Continue(song)
+        }
+    }
+
+    fn after_fetch(&mut self, song: &SongDescriptor, result: LyricsResult) -> LyricsResult {
+        match &mut self.after {
+            Some(after) => after(song, result),
+            None => result
+        }
+    }
+}
+
+impl Debug for FnMiddleware {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        formatter.write_str("FnMiddleware { }")
+    }
+}
+
+/// A [LyricsMiddleware] that logs each fetch to stderr without changing anything.
+#[derive(Debug, Default)]
+pub struct LoggingMiddleware;
+
+impl LyricsMiddleware for LoggingMiddleware {
+    fn before_fetch(&mut self, song: SongDescriptor) -> BeforeFetch {
+        eprintln!("Fetching song {:?}", song);
+
+        BeforeFetch::Continue(song)
+    }
+
+    fn after_fetch(&mut self, song: &SongDescriptor, result: LyricsResult) -> LyricsResult {
+        eprintln!("Fetched song {:?}; success: {}", song, result.is_ok());
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct FakeLyricsFetcher {
+        fetched_songs: Vec<SongDescriptor>,
+    }
+
+    impl LyricsFetcher for FakeLyricsFetcher {
+        fn fetch_lyrics(&mut self, song: &SongDescriptor) -> Result<String, FetchError> {
+            self.fetched_songs.push(song.clone());
+
+            Ok(format!("lyrics for {}", song.name))
+        }
+    }
+
+    #[test]
+    fn decorating_lyrics_fetcher_lets_middleware_rewrite_song() {
+        let mut fetcher = DecoratingLyricsFetcher::new(FakeLyricsFetcher::default(), vec![])
+            .with_middleware(FnMiddleware::before(Box::new(|song| {
+                BeforeFetch::Continue(SongDescriptor { name: song.name.to_uppercase(), ..song })
+            })));
+
+        let result = fetcher.fetch_lyrics(&make_song("foo", "mr. foo"));
+
+        assert_eq!(result, Ok("lyrics for FOO".to_string()));
+        assert_eq!(fetcher.fetcher.fetched_songs, vec![make_song("FOO", "mr. foo")]);
+    }
+
+    #[test]
+    fn decorating_lyrics_fetcher_lets_middleware_short_circuit() {
+        let mut fetcher = DecoratingLyricsFetcher::new(FakeLyricsFetcher::default(), vec![])
+            .with_middleware(FnMiddleware::before(Box::new(|_| {
+                BeforeFetch::Respond(Err("blocked".into()))
+            })));
+
+        let result = fetcher.fetch_lyrics(&make_song("foo", "mr. foo"));
+
+        assert_eq!(result, Err("blocked".into()));
+        assert!(fetcher.fetcher.fetched_songs.is_empty());
+    }
+
+    #[test]
+    fn decorating_lyrics_fetcher_lets_middleware_transform_result() {
+        let mut fetcher = DecoratingLyricsFetcher::new(FakeLyricsFetcher::default(), vec![])
+            .with_middleware(FnMiddleware::after(Box::new(|_, result| {
+                result.map(|lyrics| lyrics.replace("foo", "***"))
+            })));
+
+        assert_eq!(fetcher.fetch_lyrics(&make_song("foo", "mr. foo")), Ok("lyrics for ***".to_string()));
+    }
+
+    #[test]
+    fn decorating_lyrics_fetcher_runs_stacked_middlewares_in_onion_order() {
+        let calls = Arc::new(Mutex::new(vec![]));
+
+        let make_middleware = |label: &'static str| {
+            let before_calls = calls.clone();
+            let after_calls = calls.clone();
+
+            FnMiddleware::new(
+                Some(Box::new(move |song| {
+                    before_calls.lock().unwrap().push(format!("before {}", label));
+
+                    BeforeFetch::Continue(song)
+                })),
+                Some(Box::new(move |_, result| {
+                    after_calls.lock().unwrap().push(format!("after {}", label));
+
+                    result
+                })))
+        };
+
+        let mut fetcher = DecoratingLyricsFetcher::new(FakeLyricsFetcher::default(), vec![])
+            .with_middleware(make_middleware("outer"))
+            .with_middleware(make_middleware("inner"));
+
+        fetcher.fetch_lyrics(&make_song("foo", "mr. foo")).unwrap();
+
+        assert_eq!(*calls.lock().unwrap(), vec!["before outer", "before inner", "after inner", "after outer"]);
+    }
+
+    #[test]
+    fn decorating_lyrics_fetcher_only_unwinds_middlewares_outside_short_circuit() {
+        let after_calls = Arc::new(Mutex::new(0));
+        let counted_after_calls = after_calls.clone();
+
+        let mut fetcher = DecoratingLyricsFetcher::new(FakeLyricsFetcher::default(), vec![])
+            .with_middleware(FnMiddleware::after(Box::new(move |_, result| {
+                *counted_after_calls.lock().unwrap() += 1;
+
+                result.map(|lyrics| format!("{}!", lyrics))
+            })))
+            .with_middleware(FnMiddleware::before(Box::new(|_| BeforeFetch::Respond(Ok("cached".to_string())))))
+            .with_middleware(FnMiddleware::after(Box::new(|_, _| panic!("should not be called"))));
+
+        assert_eq!(fetcher.fetch_lyrics(&make_song("foo", "mr. foo")), Ok("cached!".to_string()));
+        assert_eq!(*after_calls.lock().unwrap(), 1);
     }
 }
\ No newline at end of file
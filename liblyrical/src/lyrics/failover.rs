@@ -12,15 +12,20 @@ impl FailoverLyricsFetcher {
 }
 
 impl LyricsFetcher for FailoverLyricsFetcher {
-    fn fetch_lyrics(&mut self, song: &SongDescriptor) -> Result<String, String> {
+    fn fetch_lyrics(&mut self, song: &SongDescriptor) -> Result<String, FetchError> {
+        let mut errors = vec![];
+
         for fetcher in &mut self.fetchers {
             match fetcher.fetch_lyrics(song) {
                 res @ Ok(_) => return res,
                 // TODO: make printing this configurable via args.
-                Err(err) => println!("Failed to fetch lyrics for song {:?} using {:?}: {}", song, fetcher, err)
+                Err(err) => {
+                    eprintln!("Failed to fetch lyrics for song {:?} using {:?}: {}", song, fetcher, err);
+                    errors.push(err);
+                }
             };
         }
 
-        Err(format!("Failed to fetch lyrics for {:?}", song))
+        Err(summarize_failures(format!("Failed to fetch lyrics for {:?}", song), &errors))
     }
 }
\ No newline at end of file
@@ -0,0 +1,150 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Why the lyrics for a song couldn't be fetched, broadly.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FailureCategory {
+    /// The song couldn't be found.
+    NotFound,
+    /// The lyrics site couldn't be reached.
+    Network,
+    /// The lyrics site's pages couldn't be made sense of.
+    Parse,
+    /// The cache couldn't be read or written.
+    Cache,
+    /// The song wasn't looked up at all, e.g. because the run was stopped first.
+    Skipped,
+    Other,
+}
+
+impl FailureCategory {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FailureCategory::NotFound => "not-found",
+            FailureCategory::Network => "network",
+            FailureCategory::Parse => "parse",
+            FailureCategory::Cache => "cache",
+            FailureCategory::Skipped => "skipped",
+            FailureCategory::Other => "other",
+        }
+    }
+}
+
+/// Why fetching the lyrics for a song failed: what went wrong and, broadly, why.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(from = "SerializedFetchError")]
+pub struct FetchError {
+    pub category: FailureCategory,
+    pub message: String,
+}
+
+impl FetchError {
+    pub fn new(category: FailureCategory, message: impl Into<String>) -> Self {
+        FetchError { category, message: message.into() }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        FetchError::new(FailureCategory::NotFound, message)
+    }
+
+    pub fn network(message: impl Into<String>) -> Self {
+        FetchError::new(FailureCategory::Network, message)
+    }
+
+    pub fn parse(message: impl Into<String>) -> Self {
+        FetchError::new(FailureCategory::Parse, message)
+    }
+
+    pub fn cache(message: impl Into<String>) -> Self {
+        FetchError::new(FailureCategory::Cache, message)
+    }
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Errors that don't say why they happened are [FailureCategory::Other]s.
+impl From<String> for FetchError {
+    fn from(message: String) -> Self {
+        FetchError::new(FailureCategory::Other, message)
+    }
+}
+
+impl From<&str> for FetchError {
+    fn from(message: &str) -> Self {
+        FetchError::from(message.to_string())
+    }
+}
+
+/// A [FetchError] as it's read back: with its category, or as the bare message
+/// caches and checkpoints written before errors had categories hold.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SerializedFetchError {
+    Categorized { category: FailureCategory, message: String },
+    Message(String),
+}
+
+impl From<SerializedFetchError> for FetchError {
+    fn from(err: SerializedFetchError) -> Self {
+        match err {
+            SerializedFetchError::Categorized { category, message } => FetchError::new(category, message),
+            SerializedFetchError::Message(message) => FetchError::from(message)
+        }
+    }
+}
+
+/// The error for a song that every attempt at fetching failed for with [errors]:
+/// [summary], followed by the first of [errors] if they all failed for the same
+/// reason and it wasn't that the song couldn't be found (like being offline), so
+/// that reason isn't lost. Otherwise, the song counts as not found.
+pub(crate) fn summarize_failures(summary: String, errors: &[FetchError]) -> FetchError {
+    let category = match errors.first() {
+        Some(err) => err.category,
+        None => return FetchError::not_found(summary)
+    };
+
+    let same_category = errors.iter().all(|err| err.category == category);
+
+    match same_category && category != FailureCategory::NotFound {
+        true => FetchError::new(category, format!("{}: {}", summary, errors[0])),
+        false => FetchError::not_found(summary)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fetch_errors_read_back_with_or_without_a_category() {
+        let network = FetchError::network("timed out");
+        let written = serde_json::to_string(&network).unwrap();
+
+        assert_eq!(written, r#"{"category":"network","message":"timed out"}"#);
+        assert_eq!(serde_json::from_str::<FetchError>(&written).unwrap(), network);
+        assert_eq!(serde_json::from_str::<FetchError>(r#""nope""#).unwrap(), FetchError::new(FailureCategory::Other, "nope"));
+    }
+
+    #[test]
+    fn summarize_failures_keeps_a_shared_cause() {
+        let summary = || "Failed to fetch lyrics for foo".to_string();
+        let network = FetchError::network("Failed to retrieve search content for song \"..\": timed out");
+        let not_found = FetchError::not_found("Failed to find a search result for song \"..\" in response: <html>");
+
+        assert_eq!(
+            summarize_failures(summary(), &[network.clone(), network.clone()]),
+            FetchError::network(format!("{}: {}", summary(), network)));
+        assert_eq!(summarize_failures(summary(), &[network.clone(), not_found.clone()]), FetchError::not_found(summary()));
+        assert_eq!(summarize_failures(summary(), &[not_found]), FetchError::not_found(summary()));
+        assert_eq!(
+            summarize_failures(summary(), &[network, FetchError::parse("bad html")]),
+            FetchError::not_found(summary()));
+        assert_eq!(summarize_failures(summary(), &[]), FetchError::not_found(summary()));
+    }
+}
@@ -0,0 +1,72 @@
+use std::time::Instant;
+
+use crate::metrics::Metrics;
+use super::*;
+
+/// A [LyricsFetcher] that records the success rate and latency of the fetcher it
+/// wraps under a `source` label.
+#[derive(Debug)]
+pub struct MeasuringLyricsFetcher<T: LyricsFetcher> {
+    fetcher: T,
+    source: String,
+    metrics: Metrics,
+}
+
+impl<T: LyricsFetcher> MeasuringLyricsFetcher<T> {
+    pub fn new(fetcher: T, source: &str, metrics: Metrics) -> Self {
+        MeasuringLyricsFetcher { fetcher, source: source.to_string(), metrics }
+    }
+}
+
+impl<T: LyricsFetcher> LyricsFetcher for MeasuringLyricsFetcher<T> {
+    fn fetch_lyrics(&mut self, song: &SongDescriptor) -> Result<String, FetchError> {
+        let start = Instant::now();
+        let result = self.fetcher.fetch_lyrics(song);
+
+        self.metrics.observe_duration(
+            "lyrical_fetch_duration_seconds",
+            &[("source", &self.source)],
+            start.elapsed());
+
+        self.metrics.increment_counter(
+            "lyrical_fetch_total",
+            &[("source", &self.source), ("result", if result.is_ok() { "success" } else { "failure" })]);
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug)]
+    struct FakeLyricsFetcher {
+        result: Result<String, FetchError>,
+    }
+
+    impl LyricsFetcher for FakeLyricsFetcher {
+        fn fetch_lyrics(&mut self, _song: &SongDescriptor) -> Result<String, FetchError> {
+            self.result.clone()
+        }
+    }
+
+    #[test]
+    fn measuring_lyrics_fetcher_counts_successes_and_failures_per_source() {
+        let metrics = Metrics::new();
+        let song = SongDescriptor { name: "foo".to_string(), artist: "bar".to_string(), uri: None };
+
+        let mut succeeding = MeasuringLyricsFetcher::new(
+            FakeLyricsFetcher { result: Ok("la la la".to_string()) }, "good", metrics.clone());
+        let mut failing = MeasuringLyricsFetcher::new(
+            FakeLyricsFetcher { result: Err("nope".into()) }, "bad", metrics.clone());
+
+        succeeding.fetch_lyrics(&song).unwrap();
+        succeeding.fetch_lyrics(&song).unwrap();
+        failing.fetch_lyrics(&song).unwrap_err();
+
+        assert_eq!(metrics.counter("lyrical_fetch_total", &[("source", "good"), ("result", "success")]), 2);
+        assert_eq!(metrics.counter("lyrical_fetch_total", &[("source", "bad"), ("result", "failure")]), 1);
+        assert!(metrics.render_prometheus().contains("lyrical_fetch_duration_seconds_count{source=\"good\"} 2\n"));
+    }
+}
\ No newline at end of file
@@ -1,52 +1,122 @@
 mod caching;
 mod decorating;
 mod failover;
+mod failure;
+mod measuring;
 mod musixmatch;
+mod normalization;
 mod simplifying;
 mod song;
 
 use std::fmt::Debug;
 
+pub use caching::{CacheEntries, CacheEntry, CACHE_LOCATION};
+pub use decorating::{BeforeFetch, DecoratingLyricsFetcher, FnMiddleware, LoggingMiddleware, LyricsMiddleware, LyricsResult};
+pub use failure::{FailureCategory, FetchError};
+pub use normalization::*;
+pub use simplifying::*;
 pub use song::*;
 
+use crate::metrics;
+
 use caching::*;
-use decorating::*;
 use failover::*;
+use failure::summarize_failures;
+use measuring::*;
 use musixmatch::*;
-use simplifying::*;
 
 pub trait LyricsFetcher: Debug {
-    fn fetch_lyrics(&mut self, song: &SongDescriptor) -> Result<String, String>;
+    fn fetch_lyrics(&mut self, song: &SongDescriptor) -> Result<String, FetchError>;
 }
 
-pub fn make_lyrics_fetcher() -> impl LyricsFetcher {
-    // TODO: make this configurable via args.
-    let proxies = vec![
+impl LyricsFetcher for Box<dyn LyricsFetcher> {
+    fn fetch_lyrics(&mut self, song: &SongDescriptor) -> Result<String, FetchError> {
+        (**self).fetch_lyrics(song)
+    }
+}
+
+/// How [make_lyrics_fetcher_with_config] sets up the fetcher it makes.
+#[derive(Clone, Builder, Debug, PartialEq)]
+#[builder(setter(into))]
+pub struct LyricsFetcherConfig {
+    /// The proxies MusixMatch is tried through, in order; [None] goes direct.
+    #[builder(default = "default_proxies()")]
+    proxies: Vec<Option<String>>,
+    /// The file lyrics are cached in between runs.
+    #[builder(default = "CACHE_LOCATION.to_string()")]
+    cache_path: String,
+    /// Whether songs whose lookup failed before are looked up again rather than
+    /// failing straight from the cache.
+    #[builder(default = "true")]
+    retry_cached_failures: bool,
+}
+
+impl LyricsFetcherConfig {
+    pub fn cache_path(&self) -> &str {
+        &self.cache_path
+    }
+}
+
+impl Default for LyricsFetcherConfig {
+    fn default() -> Self {
+        LyricsFetcherConfigBuilder::default().build().unwrap()
+    }
+}
+
+fn default_proxies() -> Vec<Option<String>> {
+    vec![
         None,
-        Some("https://103.83.116.210:55443"),
-        Some("https://103.241.227.105:50313"),
-    ];
+        Some("https://103.83.116.210:55443".to_string()),
+        Some("https://103.241.227.105:50313".to_string()),
+    ]
+}
+
+pub fn make_lyrics_fetcher() -> impl LyricsFetcher {
+    // TODO: make use of this middleware configurable via args.
+    make_lyrics_fetcher_with_middlewares(vec![Box::new(LoggingMiddleware)])
+}
+
+/// Makes the default [LyricsFetcher] with [middlewares] stacked around it
+/// (outermost first).
+pub fn make_lyrics_fetcher_with_middlewares(middlewares: Vec<Box<dyn LyricsMiddleware>>) -> impl LyricsFetcher {
+    make_lyrics_fetcher_with_config(&LyricsFetcherConfig::default(), middlewares)
+}
+
+/// Makes a [LyricsFetcher] set up by [config] with [middlewares] stacked around it
+/// (outermost first).
+pub fn make_lyrics_fetcher_with_config(config: &LyricsFetcherConfig, middlewares: Vec<Box<dyn LyricsMiddleware>>) -> impl LyricsFetcher {
+    let proxies = &config.proxies;
+
+    // Where the simplifying fetcher reports which simplification found a song so
+    // the cache can remember it.
+    let simplifications = SimplificationLog::new();
 
     // The main fetcher that will be used for retrieiving song lyrics we don't
     // have cached yet.
     let main_fetcher = FailoverLyricsFetcher::new({
         // First, try musixmatch directly with each proxy.
-        let mut fetchers = make_musixmatch_fetchers(proxies.clone());
+        let mut fetchers = make_musixmatch_fetchers(proxies, None);
 
-        // Next, try simplifying the song name and hitting musixmatch again.
+        // Next, try simplified and normalized versions of the song (stripping
+        // release details, accents, aliases, etc.) and hitting musixmatch again.
         fetchers.push(Box::new(
-            SimplifyingLyricsFetcher::new(
-                FailoverLyricsFetcher::new(
-                    make_musixmatch_fetchers(proxies.clone())))));
+            MeasuringLyricsFetcher::new(
+                SimplifyingLyricsFetcher::new(
+                    FailoverLyricsFetcher::new(
+                        make_musixmatch_fetchers(proxies, Some("simplifying"))))
+                    .with_normalization(ArtistAliases::with_defaults())
+                    .with_log(simplifications.clone()),
+                "simplifying",
+                metrics::global())));
 
         fetchers
     });
 
     // The cache to use by the [CachingLyricsFetcher] we're going to construct.
     let cache = DevCache::new(
-        // TODO: make this configurable via args.
         DevCacheOptionsBuilder::default()
             .write_eagerly(false)
+            .path(config.cache_path.clone())
             .build()
             .unwrap()
     );
@@ -56,38 +126,44 @@ pub fn make_lyrics_fetcher() -> impl LyricsFetcher {
     let caching_fetcher = CachingLyricsFetcher::new(
         main_fetcher, 
         cache, 
-        // TODO: make this configurable via args.
         CachingLyricsFetcherOptionsBuilder::default()
             .cache_failures(true)
-            .retry_cached_failures(true)
+            .retry_cached_failures(config.retry_cached_failures)
+            .metrics(Some(metrics::global()))
+            .simplifications(Some(simplifications))
             .build()
             .unwrap()
     );
 
-    // TODO: make use of this fetcher configurable via args.
-    // A fetcher that will log periodic results to stdout.
-    let logging_fetcher = DecoratingLyricsFetcher::new(
-        caching_fetcher,
-        Box::new(|song| println!("Fetching song {:?}", song)),
-        Box::new(|song, result|
-            println!("Fetched song {:?}; success: {}", song, result.is_ok()))
-    );
-
-    logging_fetcher
+    // A fetcher that will run [middlewares] around every fetch.
+    DecoratingLyricsFetcher::new(caching_fetcher, middlewares)
 }
 
-fn make_musixmatch_fetchers<'a>(proxies: Vec<Option<&'a str>>) -> Vec<Box<dyn LyricsFetcher>> {
+/// Makes a musixmatch fetcher for each of [proxies], labelling their metrics
+/// with [stage] (e.g. "simplifying/musixmatch") when they're nested inside one.
+fn make_musixmatch_fetchers(proxies: &[Option<String>], stage: Option<&str>) -> Vec<Box<dyn LyricsFetcher>> {
     proxies.iter()
         .map(|proxy| {
             let options = MusixMatchLyricsFetcherOptionsBuilder::default()
-                .proxy(match proxy {
-                    Some(proxy) => Some(proxy.to_string()),
-                    None => None
-                })
+                .proxy(proxy.clone())
                 .build()
                 .unwrap();
 
-            Box::new(MusixMatchLyricsFetcher::new(options)) as Box<dyn LyricsFetcher>
+            // Label each fetcher by the proxy it goes through so we can tell
+            // which proxies are actually pulling their weight.
+            let source = match proxy {
+                Some(proxy) => format!("musixmatch via {}", proxy),
+                None => "musixmatch".to_string()
+            };
+            let source = match stage {
+                Some(stage) => format!("{}/{}", stage, source),
+                None => source
+            };
+
+            Box::new(MeasuringLyricsFetcher::new(
+                MusixMatchLyricsFetcher::new(options),
+                &source,
+                metrics::global())) as Box<dyn LyricsFetcher>
         })
         .collect()
 }
\ No newline at end of file
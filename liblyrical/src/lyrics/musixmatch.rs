@@ -25,7 +25,7 @@ impl MusixMatchLyricsFetcher {
         MusixMatchLyricsFetcher{ options }
     }
 
-    fn get_song_uri(&self, song: &SongDescriptor) -> Result<String, String> {
+    fn get_song_uri(&self, song: &SongDescriptor) -> Result<String, FetchError> {
         match &song.uri {
             // If we have the uri available, just use it directly.
             Some(SongUri::MusixMatchUri(uri)) => Ok(uri.clone()),
@@ -43,16 +43,16 @@ impl MusixMatchLyricsFetcher {
 
                 let search_result_html = client
                     .execute(request)
-                    .map_err(|err| format!("Failed to retrieve search content for song \"{:?}\": {}", song, err))
+                    .map_err(|err| FetchError::network(format!("Failed to retrieve search content for song \"{:?}\": {}", song, err)))
                     .and_then(|resp| {
                         resp.text()
-                            .map_err(|err| format!("Failed to extract response body when searching for song \"{:?}\": {}", song, err))
+                            .map_err(|err| FetchError::network(format!("Failed to extract response body when searching for song \"{:?}\": {}", song, err)))
                     })?;
                 
                 let search_result = Html::parse_document(&search_result_html);
 
                 let result_url_meta_selector = Selector::parse(MUSIX_MATCH_SEARCH_TRACK_URI_SELECTOR)
-                    .map_err(|err| format!("Failed to parse selector for musix match search track uri selector for song \"{:?}\": {:?}", song, err))?;
+                    .map_err(|err| FetchError::parse(format!("Failed to parse selector for musix match search track uri selector for song \"{:?}\": {:?}", song, err)))?;
 
                 let uri = search_result
                     .select(&result_url_meta_selector)
@@ -61,7 +61,7 @@ impl MusixMatchLyricsFetcher {
                 
                 match uri {
                     Some(uri) => Ok(format!("{}{}", MUSIX_MATCH_URI, uri)),
-                    None => Err(format!("Failed to find a search result for song \"{:?}\" in response: {}", song, search_result_html))
+                    None => Err(FetchError::not_found(format!("Failed to find a search result for song \"{:?}\" in response: {}", song, search_result_html)))
                 }
             }
         }
@@ -89,7 +89,7 @@ impl MusixMatchLyricsFetcher {
 }
 
 impl LyricsFetcher for MusixMatchLyricsFetcher {
-    fn fetch_lyrics(&mut self, song: &SongDescriptor) -> Result<String, String> {
+    fn fetch_lyrics(&mut self, song: &SongDescriptor) -> Result<String, FetchError> {
         let uri = self.get_song_uri(song)?;
 
         let client = self.make_client();
@@ -101,12 +101,12 @@ impl LyricsFetcher for MusixMatchLyricsFetcher {
 
         let content_html = client.execute(request)
             .and_then(|resp| resp.text())
-            .map_err(|err| format!("Failed to extract html from response: {}", err))?;
+            .map_err(|err| FetchError::network(format!("Failed to extract html from response: {}", err)))?;
         
         let content = Html::parse_document(&content_html);
 
         let segments_selector = Selector::parse(MUSIX_MATCH_LYRICS_SEGMENT_SELECTOR)
-            .map_err(|err| format!("failed to parse MusixMatch lyrics segment selector: {:?}", err))?;
+            .map_err(|err| FetchError::parse(format!("failed to parse MusixMatch lyrics segment selector: {:?}", err)))?;
 
         let lyrics = content.select(&segments_selector)
             .map(|element_ref| element_ref.inner_html())
@@ -118,7 +118,7 @@ impl LyricsFetcher for MusixMatchLyricsFetcher {
 
         match lyrics {
             Some(lyrics) => Ok(lyrics),
-            None => Err(format!("Something went unexpectedly wrong while fetching lyrics for song \"{:?}\" with html: {}", song, &content_html))
+            None => Err(FetchError::parse(format!("Something went unexpectedly wrong while fetching lyrics for song \"{:?}\" with html: {}", song, &content_html)))
         }
     }
 }
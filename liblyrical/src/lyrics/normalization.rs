@@ -0,0 +1,306 @@
+use std::collections::HashMap;
+
+use regex;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+use super::*;
+
+/// Keywords that mark a bracketed or hyphenated suffix of a song name as describing
+/// a particular release of the song rather than the song itself.
+const VERSION_KEYWORDS: &str = r"remaster(?:ed)?|live|radio edit|edit|version|mix|mono|stereo|deluxe|bonus track|explicit|clean|acoustic|demo|single";
+
+/// Groups of names that refer to the same artist.
+const DEFAULT_ARTIST_ALIASES: [&[&str]; 6] = [
+    &["Beyoncé", "Beyonce"],
+    &["JAY-Z", "Jay Z", "Jay-Z"],
+    &["P!nk", "Pink"],
+    &["Ke$ha", "Kesha"],
+    &["The Weeknd", "Weeknd"],
+    &["Prince", "The Artist Formerly Known as Prince"],
+];
+
+/// A table of artist names that should be treated as interchangeable.
+#[derive(Clone, Debug, Default)]
+pub struct ArtistAliases {
+    groups: Vec<Vec<String>>,
+    index: HashMap<String, usize>,
+}
+
+impl ArtistAliases {
+    pub fn new() -> Self {
+        ArtistAliases::default()
+    }
+
+    /// Makes an [ArtistAliases] populated with a handful of well-known aliases.
+    pub fn with_defaults() -> Self {
+        DEFAULT_ARTIST_ALIASES.iter()
+            .fold(ArtistAliases::new(), |mut acc, group| {
+                acc.add(group.iter().map(|name| name.to_string()).collect());
+
+                acc
+            })
+    }
+
+    /// Adds a group of names that all refer to the same artist; if any of them are
+    /// already known, the groups are merged.
+    pub fn add(&mut self, names: Vec<String>) {
+        let existing_group = names.iter()
+            .find_map(|name| self.index.get(&normalize_for_comparison(name)).cloned());
+
+        let group = match existing_group {
+            Some(group) => group,
+            None => {
+                self.groups.push(vec![]);
+                self.groups.len() - 1
+            }
+        };
+
+        for name in names {
+            let key = normalize_for_comparison(&name);
+
+            if !self.groups[group].contains(&name) {
+                self.groups[group].push(name);
+            }
+
+            self.index.insert(key, group);
+        }
+    }
+
+    /// Returns every other name known for [artist].
+    pub fn aliases_of(&self, artist: &str) -> Vec<String> {
+        let key = normalize_for_comparison(artist);
+
+        match self.index.get(&key) {
+            Some(group) => self.groups[*group].iter()
+                .filter(|name| name.as_str() != artist)
+                .cloned()
+                .collect(),
+            None => vec![]
+        }
+    }
+}
+
+/// Removes accents and other combining marks ("Beyoncé" => "Beyonce").
+pub fn fold_diacritics(text: &str) -> String {
+    text.nfkd()
+        .filter(|c| !is_combining_mark(*c))
+        .collect()
+}
+
+/// Removes accents like [fold_diacritics] and spells out the letters that don't
+/// decompose into a plain one and an accent ("Straße" => "Strasse", "Mø" => "Mo").
+pub fn transliterate(text: &str) -> String {
+    fold_diacritics(text)
+        .chars()
+        .fold(String::new(), |mut acc, c| {
+            match c {
+                'ß' => acc.push_str("ss"),
+                'æ' => acc.push_str("ae"),
+                'Æ' => acc.push_str("AE"),
+                'œ' => acc.push_str("oe"),
+                'Œ' => acc.push_str("OE"),
+                'ø' => acc.push('o'),
+                'Ø' => acc.push('O'),
+                'ł' => acc.push('l'),
+                'Ł' => acc.push('L'),
+                'đ' => acc.push('d'),
+                'Đ' => acc.push('D'),
+                '‘' | '’' => acc.push('\''),
+                '“' | '”' => acc.push('"'),
+                c => acc.push(c)
+            };
+
+            acc
+        })
+}
+
+/// Removes release details like "(feat. X)", "[Remastered 2011]", "(Live at ...)" and
+/// " - Radio Edit" from a song name.
+pub fn strip_version_details(name: &str) -> String {
+    let bracketed_version_regex = regex::Regex::new(&format!(r"(?i)\s*[(\[][^)\]]*\b(?:{})\b[^)\]]*[)\]]", VERSION_KEYWORDS)).unwrap();
+    let hyphenated_version_regex = regex::Regex::new(&format!(r"(?i)\s+-\s+[^-]*\b(?:{})\b[^-]*$", VERSION_KEYWORDS)).unwrap();
+
+    let name = strip_featured_artists_from_name(name);
+    let name = bracketed_version_regex.replace_all(&name, "");
+    let name = hyphenated_version_regex.replace_all(&name, "");
+
+    name.trim().to_string()
+}
+
+/// Removes featured artists from a song name ("Crazy in Love (feat. JAY-Z)" =>
+/// "Crazy in Love").
+pub fn strip_featured_artists_from_name(name: &str) -> String {
+    let featuring_regex = regex::Regex::new(r"(?i)\s*[(\[](?:feat\.?|ft\.?|featuring|with)\s[^)\]]*[)\]]").unwrap();
+    let trailing_featuring_regex = regex::Regex::new(r"(?i)\s+(?:feat\.?|ft\.?|featuring)\s.*$").unwrap();
+
+    let name = featuring_regex.replace_all(name, "");
+    let name = trailing_featuring_regex.replace_all(&name, "");
+
+    name.trim().to_string()
+}
+
+/// Removes featured artists from an artist name ("Beyoncé feat. JAY-Z" => "Beyoncé").
+pub fn strip_featured_artists(artist: &str) -> String {
+    let featuring_regex = regex::Regex::new(r"(?i)\s*(?:[(\[]\s*)?\b(?:feat\.?|ft\.?|featuring)\s.*$").unwrap();
+
+    featuring_regex.replace_all(artist, "").trim().to_string()
+}
+
+/// Removes a leading "The " ("The Beatles" => "Beatles").
+pub fn strip_leading_the(text: &str) -> String {
+    let leading_the_regex = regex::Regex::new(r"(?i)^the\s+").unwrap();
+
+    leading_the_regex.replace(text, "").to_string()
+}
+
+/// Swaps " & " for " and " and vice versa.
+pub fn swap_ampersands(text: &str) -> String {
+    let ampersand_regex = regex::Regex::new(r"\s+&\s+").unwrap();
+    let and_regex = regex::Regex::new(r"(?i)\s+and\s+").unwrap();
+
+    match ampersand_regex.is_match(text) {
+        true => ampersand_regex.replace_all(text, " and ").to_string(),
+        false => and_regex.replace_all(text, " & ").to_string()
+    }
+}
+
+/// Reduces [text] to a form that's suitable for comparing two names regardless of
+/// casing, accents, punctuation, "&" vs "and" and a leading "The".
+pub fn normalize_for_comparison(text: &str) -> String {
+    let non_word_regex = regex::Regex::new(r"[^\w]+").unwrap();
+
+    let text = fold_diacritics(text).to_lowercase().replace('&', " and ");
+    let text = non_word_regex.replace_all(&text, " ");
+
+    strip_leading_the(text.trim())
+}
+
+/// Produces alternative descriptors for [song], most conservative first, that are
+/// likely to match the same song if [song] itself can't be found.
+///
+/// The original [song] is never included and none of the candidates have a uri.
+pub fn candidate_songs(song: &SongDescriptor, aliases: &ArtistAliases) -> Vec<SongDescriptor> {
+    let make_song = |name: &str, artist: &str| SongDescriptor {
+        name: name.to_string(),
+        artist: artist.to_string(),
+        uri: None
+    };
+
+    let name = strip_version_details(&song.name);
+    let artist = strip_featured_artists(&song.artist);
+
+    let mut candidates = vec![
+        make_song(&name, &artist),
+        make_song(&transliterate(&name), &transliterate(&artist)),
+        make_song(&swap_ampersands(&name), &swap_ampersands(&artist)),
+        make_song(&strip_leading_the(&name), &strip_leading_the(&artist)),
+    ];
+
+    for alias in aliases.aliases_of(&artist) {
+        candidates.push(make_song(&name, &alias));
+    }
+
+    candidates.into_iter()
+        .fold(vec![], |mut acc, candidate| {
+            let is_original = candidate.name == song.name && candidate.artist == song.artist;
+
+            if !is_original && !candidate.name.is_empty() && !acc.contains(&candidate) {
+                acc.push(candidate);
+            }
+
+            acc
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn strip_version_details_removes_featured_artists() {
+        assert_eq!(strip_version_details("Crazy in Love (feat. JAY-Z)"), "Crazy in Love");
+        assert_eq!(strip_version_details("Crazy in Love [ft. JAY-Z]"), "Crazy in Love");
+        assert_eq!(strip_version_details("Crazy in Love featuring JAY-Z"), "Crazy in Love");
+    }
+
+    #[test]
+    fn strip_version_details_removes_release_details() {
+        assert_eq!(strip_version_details("Let It Be [Remastered 2009]"), "Let It Be");
+        assert_eq!(strip_version_details("Let It Be (Live at the Rooftop)"), "Let It Be");
+        assert_eq!(strip_version_details("Let It Be - Radio Edit"), "Let It Be");
+        assert_eq!(strip_version_details("Let It Be - Remastered 2009"), "Let It Be");
+    }
+
+    #[test]
+    fn strip_version_details_keeps_meaningful_brackets() {
+        assert_eq!(strip_version_details("(I Can't Get No) Satisfaction"), "(I Can't Get No) Satisfaction");
+    }
+
+    #[test]
+    fn strip_featured_artists_keeps_primary_artist() {
+        assert_eq!(strip_featured_artists("Beyoncé feat. JAY-Z"), "Beyoncé");
+        assert_eq!(strip_featured_artists("Beyoncé (ft. JAY-Z)"), "Beyoncé");
+        assert_eq!(strip_featured_artists("Simon & Garfunkel"), "Simon & Garfunkel");
+    }
+
+    #[test]
+    fn transliterate_replaces_special_characters() {
+        assert_eq!(transliterate("Møtley Crüe"), "Motley Crue");
+        assert_eq!(transliterate("Straße"), "Strasse");
+    }
+
+    #[test]
+    fn fold_diacritics_removes_accents() {
+        assert_eq!(fold_diacritics("Beyoncé"), "Beyonce");
+        assert_eq!(fold_diacritics("Sigur Rós"), "Sigur Ros");
+    }
+
+    #[test]
+    fn swap_ampersands_swaps_both_ways() {
+        assert_eq!(swap_ampersands("Simon & Garfunkel"), "Simon and Garfunkel");
+        assert_eq!(swap_ampersands("Simon and Garfunkel"), "Simon & Garfunkel");
+    }
+
+    #[test]
+    fn normalize_for_comparison_ignores_cosmetic_differences() {
+        assert_eq!(normalize_for_comparison("The Beyoncé & Co."), normalize_for_comparison("beyonce and co"));
+    }
+
+    #[test]
+    fn artist_aliases_finds_other_names_in_group() {
+        let aliases = ArtistAliases::with_defaults();
+
+        assert_eq!(aliases.aliases_of("Beyonce"), vec!["Beyoncé".to_string()]);
+        assert_eq!(aliases.aliases_of("pink"), vec!["P!nk".to_string(), "Pink".to_string()]);
+        assert!(aliases.aliases_of("Dave Rodgers").is_empty());
+    }
+
+    #[test]
+    fn artist_aliases_merges_overlapping_groups() {
+        let mut aliases = ArtistAliases::new();
+        aliases.add(vec!["Foo".to_string(), "Foo Fighters".to_string()]);
+        aliases.add(vec!["Foo Fighters".to_string(), "FF".to_string()]);
+
+        assert_eq!(aliases.aliases_of("FF"), vec!["Foo".to_string(), "Foo Fighters".to_string()]);
+    }
+
+    #[test]
+    fn candidate_songs_produces_progressively_normalized_candidates() {
+        let song = make_song("Crazy in Love (feat. JAY-Z) - Radio Edit", "Beyoncé");
+        let candidates = candidate_songs(&song, &ArtistAliases::with_defaults());
+
+        assert_eq!(candidates, vec![
+            make_song("Crazy in Love", "Beyoncé"),
+            make_song("Crazy in Love", "Beyonce"),
+        ]);
+    }
+
+    #[test]
+    fn candidate_songs_excludes_original_song() {
+        let song = make_song("Mrs. Robinson", "Simon & Garfunkel");
+        let candidates = candidate_songs(&song, &ArtistAliases::new());
+
+        assert_eq!(candidates, vec![make_song("Mrs. Robinson", "Simon and Garfunkel")]);
+    }
+}
\ No newline at end of file
@@ -1,27 +1,279 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
 use super::*;
 
 use regex;
 
+/// A strategy for turning a song into a simpler song that's more likely to be found.
+pub trait Simplifier: Debug {
+    /// A short, stable name for the strategy used when reporting simplifications.
+    fn name(&self) -> &'static str;
+
+    /// Returns a simplified version of [song], or [None] if this strategy doesn't apply.
+    fn simplify(&self, song: &SongDescriptor) -> Option<SongDescriptor>;
+}
+
+/// Records that lyrics for [original] were found by fetching [simplified] instead.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Simplification {
+    pub original: SongDescriptor,
+    pub simplified: SongDescriptor,
+    pub strategies: Vec<String>,
+}
+
+/// A shared log of the [Simplification]s that produced hits, so that whoever is
+/// upstream of a [SimplifyingLyricsFetcher] (e.g. a cache) can act on them.
+#[derive(Clone, Debug, Default)]
+pub struct SimplificationLog {
+    simplifications: Rc<RefCell<Vec<Simplification>>>,
+}
+
+impl SimplificationLog {
+    pub fn new() -> Self {
+        SimplificationLog::default()
+    }
+
+    pub fn record(&self, simplification: Simplification) {
+        self.simplifications.borrow_mut().push(simplification);
+    }
+
+    /// Removes and returns the most recent [Simplification] recorded for [song].
+    pub fn take_for(&self, song: &SongDescriptor) -> Option<Simplification> {
+        let mut simplifications = self.simplifications.borrow_mut();
+
+        let index = simplifications.iter().rposition(|simplification| &simplification.original == song)?;
+        let simplification = simplifications.remove(index);
+
+        simplifications.retain(|simplification| &simplification.original != song);
+
+        Some(simplification)
+    }
+}
+
+/// The strategy name [SimplifyingLyricsFetcher] records for hits on one of a
+/// song's normalized [candidate_songs].
+const NORMALIZE_STRATEGY: &str = "normalize";
+
+/// A [LyricsFetcher] that progressively simplifies a song using a list of
+/// [Simplifier]s, trying the fetcher it wraps after each one that applies, and then
+/// (if asked to) tries the song's normalized [candidate_songs].
+///
+/// Simplifications are cumulative: each strategy is applied to the result of the
+/// ones before it. Each distinct song is only tried once, however many strategies
+/// come up with it.
 #[derive(Debug)]
 pub struct SimplifyingLyricsFetcher<T: LyricsFetcher> {
-    fetcher: T
+    fetcher: T,
+    simplifiers: Vec<Box<dyn Simplifier>>,
+    aliases: Option<ArtistAliases>,
+    log: Option<SimplificationLog>,
 }
 
 impl<T: LyricsFetcher> SimplifyingLyricsFetcher<T> {
     pub fn new(fetcher: T) -> Self {
-        SimplifyingLyricsFetcher { fetcher }
+        SimplifyingLyricsFetcher { fetcher, simplifiers: default_simplifiers(), aliases: None, log: None }
+    }
+
+    pub fn with_simplifiers(mut self, simplifiers: Vec<Box<dyn Simplifier>>) -> Self {
+        self.simplifiers = simplifiers;
+
+        self
+    }
+
+    /// Also tries the normalized [candidate_songs] of a song, using [aliases], once
+    /// its simplifications are used up.
+    pub fn with_normalization(mut self, aliases: ArtistAliases) -> Self {
+        self.aliases = Some(aliases);
+
+        self
+    }
+
+    pub fn with_log(mut self, log: SimplificationLog) -> Self {
+        self.log = Some(log);
+
+        self
+    }
+
+    /// The songs to try in place of [song], in order, with the strategies that came
+    /// up with each; songs that are the same as [song] or an earlier candidate are
+    /// left out.
+    fn candidates(&self, song: &SongDescriptor) -> Vec<(SongDescriptor, Vec<String>)> {
+        let mut candidates = vec![];
+        let mut simplified = song.clone();
+        let mut strategies = vec![];
+
+        for simplifier in &self.simplifiers {
+            let next = match simplifier.simplify(&simplified) {
+                Some(next) if next != simplified => next,
+                _ => continue
+            };
+
+            simplified = next;
+            strategies.push(simplifier.name().to_string());
+            candidates.push((simplified.clone(), strategies.clone()));
+        }
+
+        if let Some(aliases) = &self.aliases {
+            candidates.extend(candidate_songs(song, aliases)
+                .into_iter()
+                .map(|candidate| (candidate, vec![NORMALIZE_STRATEGY.to_string()])));
+        }
+
+        let is_same_song = |a: &SongDescriptor, b: &SongDescriptor| a.name == b.name && a.artist == b.artist;
+
+        candidates.into_iter()
+            .fold(vec![], |mut acc: Vec<(SongDescriptor, Vec<String>)>, (candidate, strategies)| {
+                if !is_same_song(&candidate, song) && !acc.iter().any(|(earlier, _)| is_same_song(earlier, &candidate)) {
+                    acc.push((candidate, strategies));
+                }
+
+                acc
+            })
     }
 }
 
 impl<T: LyricsFetcher> LyricsFetcher for SimplifyingLyricsFetcher<T> {
-    fn fetch_lyrics(&mut self, song: &SongDescriptor) -> Result<String, String> {
-        let song = SongDescriptor {
-            name: simplify_name(&song.name),
-            artist: song.artist.clone(),
-            uri: None
-        };
+    fn fetch_lyrics(&mut self, song: &SongDescriptor) -> Result<String, FetchError> {
+        let candidates = self.candidates(song);
+        let mut errors = vec![];
+
+        for (candidate, strategies) in &candidates {
+            match self.fetcher.fetch_lyrics(candidate) {
+                Ok(lyrics) => {
+                    if let Some(log) = &self.log {
+                        log.record(Simplification {
+                            original: song.clone(),
+                            simplified: candidate.clone(),
+                            strategies: strategies.clone(),
+                        });
+                    }
+
+                    return Ok(lyrics);
+                },
+                // TODO: make printing this configurable via args.
+                Err(err) => {
+                    eprintln!("Failed to fetch lyrics for song {:?} simplified with [{}]: {}", candidate, strategies.join(", "), err);
+                    errors.push(err);
+                }
+            };
+        }
 
-        self.fetcher.fetch_lyrics(&song)
+        Err(summarize_failures(format!("Failed to fetch lyrics for {:?} after {} simplifications", song, candidates.len()), &errors))
+    }
+}
+
+/// The [Simplifier]s used by [SimplifyingLyricsFetcher::new], in order.
+pub fn default_simplifiers() -> Vec<Box<dyn Simplifier>> {
+    vec![
+        Box::new(StripFeaturedArtists),
+        Box::new(StripParenthetical),
+        Box::new(StripAfterHyphen),
+        Box::new(PrimaryArtistOnly),
+        Box::new(Transliterate),
+    ]
+}
+
+/// Builds a simplified song, dropping the uri since it identifies the original song.
+fn make_simplified_song(song: &SongDescriptor, name: String, artist: String) -> Option<SongDescriptor> {
+    match name == song.name && artist == song.artist {
+        true => None,
+        false => Some(SongDescriptor { name, artist, uri: None })
+    }
+}
+
+/// Removes everything after " - " in the song name ("foo - Radio Edit" => "foo").
+#[derive(Debug)]
+pub struct StripAfterHyphen;
+
+impl Simplifier for StripAfterHyphen {
+    fn name(&self) -> &'static str {
+        "strip-after-hyphen"
+    }
+
+    fn simplify(&self, song: &SongDescriptor) -> Option<SongDescriptor> {
+        make_simplified_song(song, simplify_name(&song.name), song.artist.clone())
+    }
+}
+
+/// Removes parenthesized and bracketed text from the song name.
+#[derive(Debug)]
+pub struct StripParenthetical;
+
+impl Simplifier for StripParenthetical {
+    fn name(&self) -> &'static str {
+        "strip-parenthetical"
+    }
+
+    fn simplify(&self, song: &SongDescriptor) -> Option<SongDescriptor> {
+        let parenthetical_regex = regex::Regex::new(r"\s*(?:\([^)]*\)|\[[^\]]*\])").unwrap();
+
+        let name = parenthetical_regex.replace_all(&song.name, "").trim().to_string();
+
+        match name.is_empty() {
+            true => None,
+            false => make_simplified_song(song, name, song.artist.clone())
+        }
+    }
+}
+
+/// Removes featured artists from both the song name and the artist.
+#[derive(Debug)]
+pub struct StripFeaturedArtists;
+
+impl Simplifier for StripFeaturedArtists {
+    fn name(&self) -> &'static str {
+        "strip-featured-artists"
+    }
+
+    fn simplify(&self, song: &SongDescriptor) -> Option<SongDescriptor> {
+        make_simplified_song(song, strip_featured_artists_from_name(&song.name), strip_featured_artists(&song.artist))
+    }
+}
+
+/// Keeps only the first of several credited artists ("Simon & Garfunkel" => "Simon").
+///
+/// Artists are only split on punctuation and featuring credits, since words like
+/// "and" and "x" are often part of a single artist's name ("Florence and the
+/// Machine", "Malcolm X").
+#[derive(Debug)]
+pub struct PrimaryArtistOnly;
+
+impl Simplifier for PrimaryArtistOnly {
+    fn name(&self) -> &'static str {
+        "primary-artist-only"
+    }
+
+    fn simplify(&self, song: &SongDescriptor) -> Option<SongDescriptor> {
+        let artist_separator_regex = regex::Regex::new(r"\s*[,;/&]\s*").unwrap();
+
+        let artist = strip_featured_artists(&song.artist);
+        let artist = artist_separator_regex
+            .split(&artist)
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_string();
+
+        match artist.is_empty() {
+            true => None,
+            false => make_simplified_song(song, song.name.clone(), artist)
+        }
+    }
+}
+
+/// Replaces accented and special latin characters with their plain ASCII
+/// counterparts ("Mötley Crüe" => "Motley Crue").
+#[derive(Debug)]
+pub struct Transliterate;
+
+impl Simplifier for Transliterate {
+    fn name(&self) -> &'static str {
+        "transliterate"
+    }
+
+    fn simplify(&self, song: &SongDescriptor) -> Option<SongDescriptor> {
+        make_simplified_song(song, transliterate(&song.name), transliterate(&song.artist))
     }
 }
 
@@ -42,7 +294,7 @@ mod test {
     }
 
     impl LyricsFetcher for FakeLyricsFetcher {
-        fn fetch_lyrics(&mut self, song: &SongDescriptor) -> Result<String, String> {
+        fn fetch_lyrics(&mut self, song: &SongDescriptor) -> Result<String, FetchError> {
             (self.on_fetch_lyrics)(song);
 
             Ok("".to_string())
@@ -55,6 +307,22 @@ mod test {
         }
     }
 
+    #[derive(Debug, Default)]
+    struct PickyLyricsFetcher {
+        fetched_songs: Vec<SongDescriptor>,
+    }
+
+    impl LyricsFetcher for PickyLyricsFetcher {
+        fn fetch_lyrics(&mut self, song: &SongDescriptor) -> Result<String, FetchError> {
+            self.fetched_songs.push(song.clone());
+
+            match (song.name.as_str(), song.artist.as_str()) {
+                ("Motley", "Crue") => Ok("la la la".to_string()),
+                _ => Err(FetchError::not_found("not found"))
+            }
+        }
+    }
+
     #[test]
     fn simplify_name_removes_content_after_hyphen() {
         assert_eq!(simplify_name("foo bar - baz qux quux"), "foo bar".to_string())
@@ -76,9 +344,9 @@ mod test {
 
         let mut fetcher = SimplifyingLyricsFetcher::new(fake_fetcher);
 
-        let song = SongDescriptor { 
-            name: String::from("foo bar - baz qux quux"), 
-            artist: String::from("mr. foo"), 
+        let song = SongDescriptor {
+            name: String::from("foo bar - baz qux quux"),
+            artist: String::from("mr. foo"),
             uri: None,
         };
 
@@ -92,4 +360,112 @@ mod test {
 
         assert_eq!(fetched_song, Some(expected_song));
     }
+
+    #[test]
+    fn strip_parenthetical_removes_parens_and_brackets() {
+        let song = make_song("Hello (Live) [Remastered]", "Adele");
+
+        assert_eq!(StripParenthetical.simplify(&song), Some(make_song("Hello", "Adele")));
+        assert_eq!(StripParenthetical.simplify(&make_song("Hello", "Adele")), None);
+    }
+
+    #[test]
+    fn strip_featured_artists_removes_features_from_name_and_artist() {
+        let song = make_song("Crazy in Love (feat. JAY-Z)", "Beyoncé ft. JAY-Z");
+
+        assert_eq!(StripFeaturedArtists.simplify(&song), Some(make_song("Crazy in Love", "Beyoncé")));
+    }
+
+    #[test]
+    fn primary_artist_only_keeps_first_artist() {
+        assert_eq!(PrimaryArtistOnly.simplify(&make_song("foo", "Simon & Garfunkel")), Some(make_song("foo", "Simon")));
+        assert_eq!(PrimaryArtistOnly.simplify(&make_song("foo", "Daft Punk, Pharrell")), Some(make_song("foo", "Daft Punk")));
+        assert_eq!(PrimaryArtistOnly.simplify(&make_song("foo", "Daft Punk")), None);
+    }
+
+    #[test]
+    fn primary_artist_only_only_splits_on_explicit_separators() {
+        assert_eq!(PrimaryArtistOnly.simplify(&make_song("foo", "Beyoncé ft. JAY-Z")), Some(make_song("foo", "Beyoncé")));
+        assert_eq!(PrimaryArtistOnly.simplify(&make_song("foo", "Malcolm X")), None);
+        assert_eq!(PrimaryArtistOnly.simplify(&make_song("foo", "Florence and the Machine")), None);
+        assert_eq!(PrimaryArtistOnly.simplify(&make_song("foo", "Florence and the Machine / Dizzee Rascal")), Some(make_song("foo", "Florence and the Machine")));
+    }
+
+    #[test]
+    fn transliterate_replaces_special_characters() {
+        assert_eq!(Transliterate.simplify(&make_song("Møtley", "Crüe")), Some(make_song("Motley", "Crue")));
+        assert_eq!(Transliterate.simplify(&make_song("Motley", "Crue")), None);
+    }
+
+    #[test]
+    fn simplifying_lyrics_fetcher_applies_simplifiers_cumulatively_and_logs_hit() {
+        let log = SimplificationLog::new();
+
+        let mut fetcher = SimplifyingLyricsFetcher::new(PickyLyricsFetcher::default())
+            .with_log(log.clone());
+
+        let song = SongDescriptor {
+            name: "Møtley - Live".to_string(),
+            artist: "Crüe & Friends".to_string(),
+            uri: Some(SongUri::MusixMatchUri("https://example.com".to_string()))
+        };
+
+        assert_eq!(fetcher.fetch_lyrics(&song), Ok("la la la".to_string()));
+        assert_eq!(fetcher.fetcher.fetched_songs, vec![
+            make_song("Møtley", "Crüe & Friends"),
+            make_song("Møtley", "Crüe"),
+            make_song("Motley", "Crue"),
+        ]);
+
+        assert_eq!(log.take_for(&song), Some(Simplification {
+            original: song.clone(),
+            simplified: make_song("Motley", "Crue"),
+            strategies: vec![
+                "strip-after-hyphen".to_string(),
+                "primary-artist-only".to_string(),
+                "transliterate".to_string(),
+            ],
+        }));
+        assert_eq!(log.take_for(&song), None);
+    }
+
+    #[test]
+    fn simplifying_lyrics_fetcher_uses_provided_simplifiers() {
+        let mut fetcher = SimplifyingLyricsFetcher::new(PickyLyricsFetcher::default())
+            .with_simplifiers(vec![Box::new(StripParenthetical)]);
+
+        assert!(fetcher.fetch_lyrics(&make_song("Møtley - Live", "Crüe")).is_err());
+        assert!(fetcher.fetcher.fetched_songs.is_empty());
+    }
+
+    #[test]
+    fn simplifying_lyrics_fetcher_tries_normalized_candidates_and_logs_hit() {
+        let log = SimplificationLog::new();
+
+        let mut fetcher = SimplifyingLyricsFetcher::new(PickyLyricsFetcher::default())
+            .with_simplifiers(vec![])
+            .with_normalization(ArtistAliases::with_defaults())
+            .with_log(log.clone());
+
+        let song = make_song("Motley [Remastered]", "The Crue");
+
+        assert_eq!(fetcher.fetch_lyrics(&song), Ok("la la la".to_string()));
+        assert_eq!(fetcher.fetcher.fetched_songs, vec![
+            make_song("Motley", "The Crue"),
+            make_song("Motley", "Crue"),
+        ]);
+        assert_eq!(log.take_for(&song).map(|simplification| simplification.strategies), Some(vec!["normalize".to_string()]));
+    }
+
+    #[test]
+    fn simplifying_lyrics_fetcher_tries_each_song_once() {
+        let mut fetcher = SimplifyingLyricsFetcher::new(PickyLyricsFetcher::default())
+            .with_normalization(ArtistAliases::with_defaults());
+
+        assert!(fetcher.fetch_lyrics(&make_song("Crazy in Love (feat. JAY-Z)", "Beyoncé")).is_err());
+        assert_eq!(fetcher.fetcher.fetched_songs, vec![
+            make_song("Crazy in Love", "Beyoncé"),
+            make_song("Crazy in Love", "Beyonce"),
+        ]);
+    }
 }
\ No newline at end of file
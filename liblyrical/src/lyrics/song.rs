@@ -1,8 +1,23 @@
+use std::fmt;
+use std::str::FromStr;
+
 use serde::{Serialize, Deserialize};
 
+use super::normalization::normalize_for_comparison;
+
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Hash)]
 pub enum SongUri {
-    MusixMatchUri(String)
+    MusixMatchUri(String),
+    /// A Spotify track uri ("spotify:track:..."), as found in playlist exports.
+    SpotifyUri(String),
+}
+
+impl SongUri {
+    pub fn as_str(&self) -> &str {
+        match self {
+            SongUri::MusixMatchUri(uri) | SongUri::SpotifyUri(uri) => uri
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Hash)]
@@ -16,4 +31,99 @@ impl ToString for SongDescriptor {
     fn to_string(&self) -> String {
         serde_json::to_string(self).unwrap()
     }
+}
+
+/// A [SongDescriptor] for [name] by [artist] without a uri, for tests.
+#[cfg(test)]
+pub(crate) fn make_song(name: &str, artist: &str) -> SongDescriptor {
+    SongDescriptor { name: name.to_string(), artist: artist.to_string(), uri: None }
+}
+
+/// A [SongDescriptor] for [name] by [artist] with a musixmatch [uri], for tests.
+#[cfg(test)]
+pub(crate) fn make_song_with_uri(name: &str, artist: &str, uri: &str) -> SongDescriptor {
+    SongDescriptor { uri: Some(SongUri::MusixMatchUri(uri.to_string())), ..make_song(name, artist) }
+}
+
+/// The canonical identity of a song, derived from its normalized artist and name so
+/// that cosmetic differences ("House of Fire" vs "house of fire") don't matter.
+///
+/// Songs that are only known by their uri are keyed by the uri instead.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct SongKey {
+    pub artist: String,
+    pub name: String,
+}
+
+impl SongKey {
+    pub fn new(song: &SongDescriptor) -> Self {
+        let key = SongKey {
+            artist: normalize_for_comparison(&song.artist),
+            name: normalize_for_comparison(&song.name),
+        };
+
+        match (key.is_empty(), &song.uri) {
+            (true, Some(uri)) => SongKey { artist: String::new(), name: uri.as_str().to_string() },
+            _ => key
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.artist.is_empty() && self.name.is_empty()
+    }
+}
+
+impl From<&SongDescriptor> for SongKey {
+    fn from(song: &SongDescriptor) -> Self {
+        SongKey::new(song)
+    }
+}
+
+/// Formats the key as "artist - name"; normalized names never contain " - ", so
+/// this can be parsed back with [SongKey::from_str].
+impl fmt::Display for SongKey {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{} - {}", self.artist, self.name)
+    }
+}
+
+impl FromStr for SongKey {
+    type Err = String;
+
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        match key.find(" - ") {
+            Some(index) => Ok(SongKey {
+                artist: key[..index].to_string(),
+                name: key[index + 3..].to_string(),
+            }),
+            None => Err(format!("Invalid song key \"{}\": expected \"artist - name\"", key))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn song_key_ignores_casing_and_uri() {
+        assert_eq!(
+            SongKey::new(&make_song("House of Fire", "Dave Rodgers")),
+            SongKey::new(&make_song_with_uri("house of fire", "dave rodgers", "https://example.com")));
+    }
+
+    #[test]
+    fn song_key_falls_back_to_uri() {
+        let key = SongKey::new(&make_song_with_uri("", "", "https://example.com/foo"));
+
+        assert_eq!(key, SongKey { artist: String::new(), name: "https://example.com/foo".to_string() });
+    }
+
+    #[test]
+    fn song_key_round_trips_through_string() {
+        let key = SongKey::new(&make_song("House of Fire - Extended Mix", "Dave Rodgers"));
+
+        assert_eq!(key.to_string(), "dave rodgers - house of fire extended mix");
+        assert_eq!(key.to_string().parse::<SongKey>(), Ok(key));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Write};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+/// Upper bounds (in seconds) of the buckets used for every histogram.
+const HISTOGRAM_BUCKETS: [f64; 9] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+/// Help text rendered alongside known metrics.
+const METRIC_DESCRIPTIONS: [(&str, &str); 7] = [
+    ("lyrical_fetch_total", "Number of lyrics fetches by source and result."),
+    ("lyrical_fetch_duration_seconds", "Latency of lyrics fetches by source."),
+    ("lyrical_cache_hits_total", "Number of lyrics fetches answered by the cache."),
+    ("lyrical_cache_misses_total", "Number of lyrics fetches not found in the cache."),
+    ("lyrical_cache_cached_failures_total", "Number of cache hits that were previously cached failures."),
+    ("lyrical_cache_retries_total", "Number of cached failures that were retried."),
+    ("lyrical_cache_errors_total", "Number of errors raised by the cache backend."),
+];
+
+static GLOBAL_METRICS: OnceLock<Metrics> = OnceLock::new();
+
+type Labels = Vec<(String, String)>;
+type MetricKey = (String, Labels);
+
+#[derive(Clone, Debug, Default)]
+struct Histogram {
+    buckets: [u64; HISTOGRAM_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+#[derive(Debug, Default)]
+struct MetricsState {
+    counters: BTreeMap<MetricKey, u64>,
+    histograms: BTreeMap<MetricKey, Histogram>,
+}
+
+/// A cheaply cloneable handle to a set of counters and histograms that can be
+/// rendered in the Prometheus text exposition format.
+#[derive(Clone, Default)]
+pub struct Metrics {
+    state: Arc<Mutex<MetricsState>>,
+}
+
+/// Returns the process-wide [Metrics] used by [crate::lyrics::make_lyrics_fetcher].
+pub fn global() -> Metrics {
+    GLOBAL_METRICS.get_or_init(Metrics::new).clone()
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Metrics::default()
+    }
+
+    pub fn increment_counter(&self, name: &str, labels: &[(&str, &str)]) {
+        let mut state = self.state.lock().unwrap();
+
+        *state.counters.entry(make_key(name, labels)).or_insert(0) += 1;
+    }
+
+    pub fn observe_duration(&self, name: &str, labels: &[(&str, &str)], duration: Duration) {
+        let seconds = duration.as_secs_f64();
+
+        let mut state = self.state.lock().unwrap();
+        let histogram = state.histograms.entry(make_key(name, labels)).or_default();
+
+        for (i, upper_bound) in HISTOGRAM_BUCKETS.iter().enumerate() {
+            if seconds <= *upper_bound {
+                histogram.buckets[i] += 1;
+            }
+        }
+
+        histogram.sum += seconds;
+        histogram.count += 1;
+    }
+
+    /// Returns the current value of the counter [name] with exactly [labels].
+    pub fn counter(&self, name: &str, labels: &[(&str, &str)]) -> u64 {
+        let state = self.state.lock().unwrap();
+
+        state.counters.get(&make_key(name, labels)).cloned().unwrap_or(0)
+    }
+
+    /// Renders every metric recorded so far in the Prometheus text format.
+    pub fn render_prometheus(&self) -> String {
+        let state = self.state.lock().unwrap();
+        let mut out = String::new();
+
+        let mut last_name = None;
+        for ((name, labels), value) in &state.counters {
+            if last_name != Some(name) {
+                write_header(&mut out, name, "counter");
+                last_name = Some(name);
+            }
+
+            writeln!(out, "{}{} {}", name, format_labels(labels, None), value).unwrap();
+        }
+
+        let mut last_name = None;
+        for ((name, labels), histogram) in &state.histograms {
+            if last_name != Some(name) {
+                write_header(&mut out, name, "histogram");
+                last_name = Some(name);
+            }
+
+            for (i, upper_bound) in HISTOGRAM_BUCKETS.iter().enumerate() {
+                let le = upper_bound.to_string();
+                writeln!(out, "{}_bucket{} {}", name, format_labels(labels, Some(&le)), histogram.buckets[i]).unwrap();
+            }
+
+            writeln!(out, "{}_bucket{} {}", name, format_labels(labels, Some("+Inf")), histogram.count).unwrap();
+            writeln!(out, "{}_sum{} {}", name, format_labels(labels, None), histogram.sum).unwrap();
+            writeln!(out, "{}_count{} {}", name, format_labels(labels, None), histogram.count).unwrap();
+        }
+
+        out
+    }
+}
+
+impl Debug for Metrics {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        formatter.write_str("Metrics { }")
+    }
+}
+
+fn make_key(name: &str, labels: &[(&str, &str)]) -> MetricKey {
+    let labels = labels.iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+
+    (name.to_string(), labels)
+}
+
+fn write_header(out: &mut String, name: &str, metric_type: &str) {
+    if let Some((_, description)) = METRIC_DESCRIPTIONS.iter().find(|(known, _)| *known == name) {
+        writeln!(out, "# HELP {} {}", name, description).unwrap();
+    }
+
+    writeln!(out, "# TYPE {} {}", name, metric_type).unwrap();
+}
+
+fn format_labels(labels: &Labels, le: Option<&str>) -> String {
+    let mut pairs = labels.iter()
+        .map(|(key, value)| format!("{}=\"{}\"", key, escape_label_value(value)))
+        .collect::<Vec<_>>();
+
+    if let Some(le) = le {
+        pairs.push(format!("le=\"{}\"", le));
+    }
+
+    match pairs.is_empty() {
+        true => String::new(),
+        false => format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn render_prometheus_renders_counters_with_labels() {
+        let metrics = Metrics::new();
+        metrics.increment_counter("lyrical_fetch_total", &[("source", "musixmatch"), ("result", "success")]);
+        metrics.increment_counter("lyrical_fetch_total", &[("source", "musixmatch"), ("result", "success")]);
+        metrics.increment_counter("lyrical_cache_hits_total", &[]);
+
+        assert_eq!(metrics.render_prometheus(), "\
+# HELP lyrical_cache_hits_total Number of lyrics fetches answered by the cache.
+# TYPE lyrical_cache_hits_total counter
+lyrical_cache_hits_total 1
+# HELP lyrical_fetch_total Number of lyrics fetches by source and result.
+# TYPE lyrical_fetch_total counter
+lyrical_fetch_total{source=\"musixmatch\",result=\"success\"} 2
+");
+    }
+
+    #[test]
+    fn render_prometheus_renders_cumulative_histogram_buckets() {
+        let metrics = Metrics::new();
+        metrics.observe_duration("foo_seconds", &[], Duration::from_millis(200));
+        metrics.observe_duration("foo_seconds", &[], Duration::from_secs(3));
+
+        let rendered = metrics.render_prometheus();
+
+        assert!(rendered.contains("# TYPE foo_seconds histogram\n"));
+        assert!(rendered.contains("foo_seconds_bucket{le=\"0.1\"} 0\n"));
+        assert!(rendered.contains("foo_seconds_bucket{le=\"0.25\"} 1\n"));
+        assert!(rendered.contains("foo_seconds_bucket{le=\"5\"} 2\n"));
+        assert!(rendered.contains("foo_seconds_bucket{le=\"+Inf\"} 2\n"));
+        assert!(rendered.contains("foo_seconds_sum 3.2\n"));
+        assert!(rendered.contains("foo_seconds_count 2\n"));
+    }
+
+    #[test]
+    fn render_prometheus_escapes_label_values() {
+        let metrics = Metrics::new();
+        metrics.increment_counter("foo_total", &[("source", "a \"quoted\" \\ value")]);
+
+        assert!(metrics.render_prometheus().contains("foo_total{source=\"a \\\"quoted\\\" \\\\ value\"} 1\n"));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,167 @@
+mod pronunciation;
+
+use serde::{Deserialize, Serialize};
+
+use crate::word_count::{Tokenizer, UnicodeTokenizer};
+
+pub use pronunciation::*;
+
+/// The structure of a single lyric line.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LineStructure {
+    pub line: String,
+    pub syllables: usize,
+    /// The last word of the line, which is the one that's expected to rhyme.
+    pub end_word: String,
+    /// The line's label in its stanza's rhyme scheme ("A" to "Z", then "AA", "AB",
+    /// ...); lines that rhyme share a label.
+    pub rhyme: String,
+}
+
+/// A blank-line-separated group of lines and their rhyme scheme.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StanzaStructure {
+    pub lines: Vec<LineStructure>,
+    /// The rhyme labels of every line, e.g. "AABB" or "ABAB"; separated by spaces if
+    /// the stanza has more than 26 rhymes ("A B ... Z AA").
+    pub scheme: String,
+}
+
+/// Syllables and rhyme schemes of a song's lyrics.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RhymeAnalysis {
+    pub stanzas: Vec<StanzaStructure>,
+}
+
+impl RhymeAnalysis {
+    /// The average number of syllables per line.
+    pub fn average_syllables(&self) -> f64 {
+        let lines = self.stanzas.iter()
+            .flat_map(|stanza| stanza.lines.iter())
+            .collect::<Vec<_>>();
+
+        match lines.len() {
+            0 => 0.0,
+            num_lines => lines.iter().map(|line| line.syllables).sum::<usize>() as f64 / num_lines as f64
+        }
+    }
+}
+
+/// Counts the syllables in each line of [lyrics] (as returned by
+/// [crate::lyrics::LyricsFetcher::fetch_lyrics]) and labels each stanza's end rhymes
+/// using [pronouncer]; lines without any words are skipped.
+pub fn analyze_rhymes(lyrics: &str, pronouncer: &Pronouncer) -> RhymeAnalysis {
+    let tokenizer = UnicodeTokenizer::default();
+
+    let lines = lyrics.lines()
+        .map(|line| (line.trim(), tokenizer.tokenize(line)))
+        .collect::<Vec<_>>();
+
+    let stanzas = lines.split(|(_, words)| words.is_empty())
+        .filter(|stanza| !stanza.is_empty())
+        .map(|stanza| {
+            let mut lines: Vec<LineStructure> = vec![];
+            let mut rhymes = 0;
+
+            for (line, words) in stanza {
+                let end_word = words.last().unwrap().clone();
+
+                // Lines that rhyme with an earlier line share its label; otherwise
+                // they get the next unused one.
+                let rhyme = match lines.iter().find(|earlier| pronouncer.rhymes(&earlier.end_word, &end_word)) {
+                    Some(earlier) => earlier.rhyme.clone(),
+                    None => {
+                        rhymes += 1;
+                        rhyme_label(rhymes - 1)
+                    }
+                };
+
+                lines.push(LineStructure {
+                    line: line.to_string(),
+                    syllables: words.iter().map(|word| pronouncer.syllables(word)).sum(),
+                    end_word,
+                    rhyme,
+                });
+            }
+
+            let labels = lines.iter().map(|line| line.rhyme.as_str()).collect::<Vec<_>>();
+            let scheme = match rhymes > 26 {
+                true => labels.join(" "),
+                false => labels.concat()
+            };
+
+            StanzaStructure { lines, scheme }
+        })
+        .collect();
+
+    RhymeAnalysis { stanzas }
+}
+
+/// The label of the rhyme at [index] in a stanza: "A" to "Z", then "AA", "AB", ...
+/// like spreadsheet columns.
+fn rhyme_label(index: usize) -> String {
+    let letter = (b'A' + (index % 26) as u8) as char;
+
+    match index / 26 {
+        0 => letter.to_string(),
+        prefix => format!("{}{}", rhyme_label(prefix - 1), letter)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn schemes(lyrics: &str) -> Vec<String> {
+        analyze_rhymes(lyrics, &Pronouncer::new()).stanzas
+            .into_iter()
+            .map(|stanza| stanza.scheme)
+            .collect()
+    }
+
+    #[test]
+    fn analyze_rhymes_labels_couplets() {
+        assert_eq!(
+            schemes("Welcome to the house of fire\nBurning with desire\nLet me go\nI know"),
+            vec!["AABB"]);
+    }
+
+    #[test]
+    fn analyze_rhymes_labels_alternating_rhymes() {
+        assert_eq!(
+            schemes("I fly tonight\nLet me go\nA satellite\nYou know"),
+            vec!["ABAB"]);
+    }
+
+    #[test]
+    fn analyze_rhymes_labels_each_stanza_separately() {
+        assert_eq!(
+            schemes("Stay by my side\nUntil the end of your silence\n\nI fly now\nWelcome to the house of fire!"),
+            vec!["AB", "AB"]);
+    }
+
+    #[test]
+    fn analyze_rhymes_labels_past_z() {
+        let end_words = ["a", "e", "i", "o", "u"].iter()
+            .flat_map(|vowel| ["b", "d", "f", "g", "k", "l"].iter().map(move |consonant| format!("t{}{}", vowel, consonant)))
+            .collect::<Vec<_>>();
+        let lyrics = end_words[..28].iter().map(|word| format!("Sing {}", word)).collect::<Vec<_>>().join("\n");
+
+        let scheme = schemes(&format!("{}\nSing tab", lyrics)).remove(0);
+
+        assert!(scheme.starts_with("A B C"));
+        assert!(scheme.ends_with("Y Z AA AB A"));
+        assert_eq!(rhyme_label(701), "ZZ");
+        assert_eq!(rhyme_label(702), "AAA");
+    }
+
+    #[test]
+    fn analyze_rhymes_counts_syllables() {
+        let analysis = analyze_rhymes("Welcome to the house of fire\n\n'Cause I'm runnin'", &Pronouncer::new());
+
+        assert_eq!(analysis.stanzas[0].lines[0].syllables, 8);
+        assert_eq!(analysis.stanzas[0].lines[0].end_word, "fire");
+        assert_eq!(analysis.stanzas[1].lines[0].syllables, 4);
+        assert_eq!(analysis.average_syllables(), 6.0);
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+
+const VOWELS: &str = "aeiouy";
+
+/// Looks up how words are pronounced in a CMU-style pronouncing dictionary, guessing
+/// from their spelling when they aren't in it.
+///
+/// The built-in dictionary only covers about 150 words that commonly end lyric lines,
+/// so most words are guessed unless the full CMU Pronouncing Dictionary is loaded with
+/// [Pronouncer::from_file].
+#[derive(Clone, Debug)]
+pub struct Pronouncer {
+    dictionary: HashMap<String, Vec<String>>,
+}
+
+impl Pronouncer {
+    /// Makes a [Pronouncer] using the built-in, hand-picked subset of the CMU
+    /// Pronouncing Dictionary.
+    pub fn new() -> Self {
+        Pronouncer::from_dictionary(include_str!("../../data/cmudict/en.txt"))
+    }
+
+    /// Makes a [Pronouncer] from a dictionary in the CMU format (`WORD  PH1 PH2 ...`);
+    /// lines starting with `;;;` and anything after a `#` are comments and alternate
+    /// pronunciations (`WORD(2)`) are ignored.
+    pub fn from_dictionary(dictionary: &str) -> Self {
+        let dictionary = dictionary.lines()
+            .filter(|line| !line.starts_with(";;;"))
+            .filter_map(|line| {
+                let mut parts = line.split('#').next()?.split_whitespace();
+                let word = parts.next()?;
+
+                match word.ends_with(')') {
+                    true => None,
+                    false => Some((word.to_lowercase(), parts.map(|phoneme| phoneme.to_string()).collect()))
+                }
+            })
+            .collect();
+
+        Pronouncer { dictionary }
+    }
+
+    /// Reads a dictionary in the CMU format (see [Pronouncer::from_dictionary]) from
+    /// the file at [path], e.g. a download of the full CMU Pronouncing Dictionary.
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let mut dictionary = String::new();
+
+        File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut dictionary))
+            .map_err(|err| format!("Failed to read pronouncing dictionary from {}: {}", path, err))?;
+
+        Ok(Pronouncer::from_dictionary(&dictionary))
+    }
+
+    /// The phonemes of [word], if it's in the dictionary.
+    pub fn phonemes(&self, word: &str) -> Option<&[String]> {
+        self.dictionary.get(&word.to_lowercase()).map(|phonemes| phonemes.as_slice())
+    }
+
+    /// The number of syllables in [word].
+    pub fn syllables(&self, word: &str) -> usize {
+        match self.phonemes(word) {
+            Some(phonemes) => phonemes.iter().filter(|phoneme| is_vowel_phoneme(phoneme)).count(),
+            None => guess_syllables(word)
+        }
+    }
+
+    /// Whether [a] and [b] rhyme, i.e. sound the same from their last stressed vowel on.
+    ///
+    /// When either word isn't in the dictionary, their endings are compared by spelling instead.
+    pub fn rhymes(&self, a: &str, b: &str) -> bool {
+        match (self.phonemes(a), self.phonemes(b)) {
+            (Some(a), Some(b)) => rhyming_phonemes(a) == rhyming_phonemes(b),
+            _ => {
+                let (a, b) = (rhyming_spelling(a), rhyming_spelling(b));
+
+                !a.is_empty() && a == b
+            }
+        }
+    }
+}
+
+impl Default for Pronouncer {
+    fn default() -> Self {
+        Pronouncer::new()
+    }
+}
+
+fn is_vowel_phoneme(phoneme: &str) -> bool {
+    phoneme.ends_with(|c: char| c.is_ascii_digit())
+}
+
+/// The phonemes from the last stressed vowel on, without stress markers
+/// ("satellite" => "AY T").
+fn rhyming_phonemes(phonemes: &[String]) -> Vec<&str> {
+    let start = phonemes.iter()
+        .rposition(|phoneme| phoneme.ends_with('1') || phoneme.ends_with('2'))
+        .or_else(|| phonemes.iter().rposition(|phoneme| is_vowel_phoneme(phoneme)))
+        .unwrap_or(0);
+
+    phonemes[start..].iter()
+        .map(|phoneme| phoneme.trim_end_matches(|c: char| c.is_ascii_digit()))
+        .collect()
+}
+
+/// Strips apostrophes and a silent trailing "e" ("fire" => "fir"), keeping the
+/// sounded one in a consonant + "le" ending ("table").
+fn pronounced_spelling(word: &str) -> Vec<char> {
+    let mut letters = word.to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphabetic())
+        .collect::<Vec<_>>();
+
+    let is_vowel = |c: char| VOWELS.contains(c);
+
+    let has_silent_e = match letters.as_slice() {
+        [.., before, 'l', 'e'] => is_vowel(*before),
+        [_, .., consonant, 'e'] => !is_vowel(*consonant),
+        _ => false
+    };
+
+    if has_silent_e {
+        letters.pop();
+    }
+
+    letters
+}
+
+/// Guesses the syllables in [word] by counting groups of vowels.
+fn guess_syllables(word: &str) -> usize {
+    let letters = pronounced_spelling(word);
+
+    let groups = letters.iter()
+        .enumerate()
+        .filter(|(i, c)| VOWELS.contains(**c) && (*i == 0 || !VOWELS.contains(letters[i - 1])))
+        .count();
+
+    match letters.is_empty() {
+        true => 0,
+        false => groups.max(1)
+    }
+}
+
+/// Guesses the rhyming part of [word] from its spelling: its last group of vowels
+/// and everything after it ("night" => "ight").
+fn rhyming_spelling(word: &str) -> String {
+    let letters = pronounced_spelling(word);
+
+    let last_vowel = match letters.iter().rposition(|c| VOWELS.contains(*c)) {
+        Some(last_vowel) => last_vowel,
+        None => return letters.into_iter().collect()
+    };
+
+    let start = letters[..last_vowel].iter()
+        .rposition(|c| !VOWELS.contains(*c))
+        .map(|consonant| consonant + 1)
+        .unwrap_or(0);
+
+    letters[start..].iter().collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn syllables_uses_the_dictionary() {
+        let pronouncer = Pronouncer::new();
+
+        assert_eq!(pronouncer.syllables("fire"), 2);
+        assert_eq!(pronouncer.syllables("Satellite"), 3);
+        assert_eq!(pronouncer.syllables("beautiful"), 3);
+    }
+
+    #[test]
+    fn syllables_falls_back_to_spelling() {
+        let pronouncer = Pronouncer::new();
+
+        assert_eq!(pronouncer.syllables("wonder"), 2);
+        assert_eq!(pronouncer.syllables("smile"), 1);
+        assert_eq!(pronouncer.syllables("table"), 2);
+        assert_eq!(pronouncer.syllables("runnin'"), 2);
+        assert_eq!(pronouncer.syllables("sky"), 1);
+    }
+
+    #[test]
+    fn rhymes_compares_stressed_endings() {
+        let pronouncer = Pronouncer::new();
+
+        assert!(pronouncer.rhymes("night", "satellite"));
+        assert!(pronouncer.rhymes("forever", "never"));
+        assert!(pronouncer.rhymes("fire", "desire"));
+        assert!(pronouncer.rhymes("go", "know"));
+        assert!(!pronouncer.rhymes("fire", "free"));
+    }
+
+    #[test]
+    fn rhymes_falls_back_to_spelling() {
+        let pronouncer = Pronouncer::new();
+
+        assert!(pronouncer.rhymes("bright", "delight"));
+        assert!(pronouncer.rhymes("smile", "while"));
+        assert!(!pronouncer.rhymes("smile", "smell"));
+    }
+
+    #[test]
+    fn from_dictionary_skips_comments_and_variants() {
+        let pronouncer = Pronouncer::from_dictionary(";;; comment\nTOMATO  T AH0 M EY1 T OW2\nTOMATO(2)  T AH0 M AA1 T OW2\nadios AA2 D IY0 OW1 S # spanish\n");
+
+        assert_eq!(
+            pronouncer.phonemes("tomato").map(|phonemes| phonemes.join(" ")),
+            Some("T AH0 M EY1 T OW2".to_string()));
+        assert_eq!(pronouncer.phonemes("adios").map(|phonemes| phonemes.len()), Some(5));
+    }
+}
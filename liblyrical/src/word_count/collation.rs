@@ -0,0 +1,60 @@
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use crate::lyrics::fold_diacritics;
+
+/// How words are compared when they're sorted by word.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Collation {
+    /// By Unicode code point, so "Zebra" comes before "apple" and "éclair" after "zebra".
+    #[default]
+    CodePoint,
+    /// The way a dictionary would: ignoring case and accents first ("apple", "éclair",
+    /// "Zebra"), and only falling back to code points for words that only differ by them.
+    Natural,
+}
+
+impl Collation {
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        match self {
+            Collation::CodePoint => a.cmp(b),
+            Collation::Natural => collation_key(a).cmp(&collation_key(b)).then_with(|| a.cmp(b)),
+        }
+    }
+}
+
+impl FromStr for Collation {
+    type Err = String;
+
+    fn from_str(collation: &str) -> Result<Self, Self::Err> {
+        match collation.trim().to_lowercase().as_str() {
+            "codepoint" | "code-point" => Ok(Collation::CodePoint),
+            "natural" => Ok(Collation::Natural),
+            _ => Err(format!("Unknown collation \"{}\"; expected codepoint or natural", collation))
+        }
+    }
+}
+
+/// [word] lowercased and stripped of accents ("Éclair" => "eclair").
+fn collation_key(word: &str) -> String {
+    fold_diacritics(word).to_lowercase()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn code_point_collation_compares_code_points() {
+        assert_eq!(Collation::CodePoint.compare("Zebra", "apple"), Ordering::Less);
+        assert_eq!(Collation::CodePoint.compare("éclair", "zebra"), Ordering::Greater);
+    }
+
+    #[test]
+    fn natural_collation_ignores_case_and_accents() {
+        let mut words = vec!["zebra", "éclair", "Apple", "eclair", "apple"];
+        words.sort_by(|a, b| Collation::Natural.compare(a, b));
+
+        assert_eq!(words, vec!["Apple", "apple", "eclair", "éclair", "zebra"]);
+    }
+}
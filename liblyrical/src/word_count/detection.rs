@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// The language some text was detected to be written in.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DetectedLanguage {
+    /// The language's ISO 639-3 code ("eng").
+    pub code: String,
+    /// The language's English name ("English").
+    pub name: String,
+    /// The matching [Language], if it's one we ship word lists for.
+    pub language: Option<Language>,
+    /// How sure the detection is, from 0 to 1.
+    pub confidence: f64,
+    /// Whether there was enough text for the detection to be trusted.
+    pub reliable: bool,
+}
+
+/// Detects the language [text] is written in from its character trigrams, without
+/// any network access; returns [None] when there's nothing to go on.
+pub fn detect_language(text: &str) -> Option<DetectedLanguage> {
+    let info = whatlang::detect(text)?;
+    let lang = info.lang();
+
+    let language = match lang {
+        whatlang::Lang::Eng => Some(Language::English),
+        whatlang::Lang::Spa => Some(Language::Spanish),
+        whatlang::Lang::Fra => Some(Language::French),
+        whatlang::Lang::Deu => Some(Language::German),
+        whatlang::Lang::Por => Some(Language::Portuguese),
+        whatlang::Lang::Ita => Some(Language::Italian),
+        _ => None
+    };
+
+    Some(DetectedLanguage {
+        code: lang.code().to_string(),
+        name: lang.eng_name().to_string(),
+        language,
+        confidence: info.confidence(),
+        reliable: info.is_reliable(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn detect_language_detects_supported_languages() {
+        let english = detect_language("Welcome to the house of fire, let me go, I just wanna let you go").unwrap();
+        let spanish = detect_language("Bienvenido a la casa del fuego, déjame ir, sólo quiero dejarte ir").unwrap();
+
+        assert_eq!(english.language, Some(Language::English));
+        assert_eq!(english.code, "eng");
+        assert_eq!(english.name, "English");
+        assert!(english.confidence > 0.0 && english.confidence <= 1.0);
+        assert_eq!(spanish.language, Some(Language::Spanish));
+    }
+
+    #[test]
+    fn detect_language_detects_unsupported_languages() {
+        let detected = detect_language("Добро пожаловать в дом огня, отпусти меня").unwrap();
+
+        assert_eq!(detected.code, "rus");
+        assert_eq!(detected.language, None);
+    }
+
+    #[test]
+    fn detect_language_needs_text() {
+        assert_eq!(detect_language("1999 !!!"), None);
+    }
+}
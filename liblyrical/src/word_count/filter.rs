@@ -0,0 +1,86 @@
+use regex::Regex;
+
+use super::*;
+
+/// Controls which words [WordCountFilter::apply] keeps and in what order.
+#[derive(Clone, Default, Builder, Debug)]
+#[builder(setter(into), default)]
+pub struct WordCountFilter {
+    /// How the words that are kept are sorted.
+    sort_order: SortOrder,
+    /// How words are compared when sorting by word or breaking ties.
+    collation: Collation,
+    /// The most words to keep, after sorting.
+    top: Option<usize>,
+    /// The fewest times a word must be used to be kept.
+    min_count: Option<i32>,
+    /// Only words matching this pattern are kept.
+    include: Option<Regex>,
+    /// Words matching this pattern are dropped.
+    exclude: Option<Regex>,
+}
+
+impl WordCountFilter {
+    /// The words in [word_counts] that pass the filter, sorted by [sort_order] and cut
+    /// down to the [top] ones.
+    pub fn apply<'a>(&self, word_counts: &'a WordCounts) -> Vec<(&'a String, &'a i32)> {
+        let mut kvps = sort_word_counts_with_collation(word_counts, self.sort_order, self.collation);
+
+        kvps.retain(|(word, count)| self.keeps(word, **count));
+
+        if let Some(top) = self.top {
+            kvps.truncate(top);
+        }
+
+        kvps
+    }
+
+    fn keeps(&self, word: &str, count: i32) -> bool {
+        self.min_count.map(|min_count| count >= min_count).unwrap_or(true)
+            && self.include.as_ref().map(|include| include.is_match(word)).unwrap_or(true)
+            && !self.exclude.as_ref().map(|exclude| exclude.is_match(word)).unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use maplit::hashmap;
+
+    use super::*;
+    use crate::utils::*;
+
+    fn word_counts() -> WordCounts {
+        stringify_map_keys(&hashmap!{ "fire" => 4, "house" => 3, "go" => 3, "let" => 2, "me" => 1 })
+    }
+
+    #[test]
+    fn apply_keeps_the_top_words() {
+        let word_counts = word_counts();
+        let filter = WordCountFilterBuilder::default().top(3).build().unwrap();
+
+        assert_eq!(
+            filter.apply(&word_counts),
+            vec![(&"fire".to_string(), &4), (&"go".to_string(), &3), (&"house".to_string(), &3)]);
+    }
+
+    #[test]
+    fn apply_filters_by_count_and_pattern() {
+        let word_counts = word_counts();
+        let filter = WordCountFilterBuilder::default()
+            .sort_order(SortOrder::Alphabetical)
+            .min_count(2)
+            .include(Regex::new("^[a-h]").unwrap())
+            .exclude(Regex::new("^go$").unwrap())
+            .build()
+            .unwrap();
+
+        assert_eq!(filter.apply(&word_counts), vec![(&"fire".to_string(), &4), (&"house".to_string(), &3)]);
+    }
+
+    #[test]
+    fn default_filter_keeps_everything() {
+        let word_counts = word_counts();
+
+        assert_eq!(WordCountFilter::default().apply(&word_counts), sort_word_counts(&word_counts, SortOrder::Descending));
+    }
+}
@@ -0,0 +1,438 @@
+mod collation;
+mod detection;
+mod filter;
+mod ngrams;
+mod stemming;
+mod stop_words;
+mod tokenizer;
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+pub use collation::*;
+pub use detection::*;
+pub use filter::*;
+pub use ngrams::*;
+pub use stemming::*;
+pub use stop_words::*;
+pub use tokenizer::*;
+
+pub type WordCounts = HashMap<String, i32>;
+
+/// [WordCounts] ordered by word, for output that needs to be the same from run to run.
+pub type OrderedWordCounts = BTreeMap<String, i32>;
+
+/// How [sort_word_counts] orders words.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Least used first; words used equally often are sorted by word.
+    Ascending,
+    /// Most used first; words used equally often are sorted by word.
+    #[default]
+    Descending,
+    /// By word, regardless of count.
+    Alphabetical,
+}
+
+impl FromStr for SortOrder {
+    type Err = String;
+
+    fn from_str(sort_order: &str) -> Result<Self, Self::Err> {
+        match sort_order.trim().to_lowercase().as_str() {
+            "asc" | "ascending" => Ok(SortOrder::Ascending),
+            "desc" | "descending" => Ok(SortOrder::Descending),
+            "alpha" | "alphabetical" => Ok(SortOrder::Alphabetical),
+            _ => Err(format!("Unknown sort order \"{}\"; expected asc, desc or alpha", sort_order))
+        }
+    }
+}
+
+/// Controls how [count_words_with_options] turns text into word counts.
+#[derive(Clone, Default, Builder, Debug)]
+#[builder(setter(into), default)]
+pub struct WordCountOptions {
+    tokenizer: UnicodeTokenizerOptions,
+    /// Whether contractions like "i'm" and "wanna" should be counted as the words
+    /// they stand for.
+    expand_contractions: bool,
+    /// Words that shouldn't be counted at all.
+    stop_words: Option<StopWords>,
+    /// Whether irregular English forms ("went", "was") should be counted as their
+    /// lemma ("go", "be").
+    lemmatize: bool,
+    /// The language whose stemmer should be used to group word forms ("go",
+    /// "going", "goes") together.
+    stemming: Option<Language>,
+    /// Whether the built-in stop words for the language the text is detected to be
+    /// in should be ignored too.
+    language_stop_words: bool,
+    /// Whether the stemmer for the language the text is detected to be in should be
+    /// used when [stemming] isn't set.
+    language_stemming: bool,
+}
+
+impl WordCountOptions {
+    /// Fills in the stop words and stemmer for the language [src] is detected to be
+    /// in, if requested, and tokenizes [src] as that language. Nothing changes unless
+    /// the language is one we have word lists for and the detection is reliable,
+    /// since another language's stop words and stemmer would mangle the counts.
+    fn for_text(&self, src: &str) -> Cow<'_, WordCountOptions> {
+        if !self.language_stop_words && !self.language_stemming {
+            return Cow::Borrowed(self);
+        }
+
+        let language = match detect_language(src) {
+            Some(DetectedLanguage { language: Some(language), reliable: true, .. }) => language,
+            _ => return Cow::Borrowed(self)
+        };
+
+        let mut options = self.clone();
+        options.tokenizer = options.tokenizer.for_language(language);
+
+        if self.language_stop_words {
+            let mut stop_words = options.stop_words.take().unwrap_or_default();
+            stop_words.extend(StopWords::for_language(language));
+
+            options.stop_words = Some(stop_words);
+        }
+
+        if self.language_stemming && options.stemming.is_none() {
+            options.stemming = Some(language);
+        }
+
+        Cow::Owned(options)
+    }
+}
+
+pub fn count_words<'a>(src: String) -> WordCounts {
+    count_words_with_tokenizer(&src, &UnicodeTokenizer::default())
+}
+
+/// Counts the words in [src], normalizing them according to [options].
+pub fn count_words_with_options(src: &str, options: &WordCountOptions) -> WordCounts {
+    let options = options.for_text(src);
+    let mut words = UnicodeTokenizer::new(options.tokenizer.clone()).tokenize(src);
+
+    if options.expand_contractions {
+        words = expand_contractions(words);
+    }
+
+    if let Some(stop_words) = &options.stop_words {
+        words.retain(|word| !stop_words.contains(word));
+    }
+
+    if options.lemmatize {
+        let lemmatizer = Lemmatizer::new();
+
+        words = words.into_iter()
+            .map(|word| lemmatizer.lemmatize(&word))
+            .collect();
+    }
+
+    let word_counts = words.into_iter()
+        .fold(HashMap::new(), |mut acc, word| {
+            *acc.entry(word).or_insert(0) += 1;
+
+            acc
+        });
+
+    match options.stemming {
+        Some(language) => {
+            let stemmer = WordStemmer::new(language);
+
+            group_word_forms(word_counts, |word| stemmer.stem(word))
+        },
+        None => word_counts
+    }
+}
+
+/// Counts the words in [src] as split up by [tokenizer].
+pub fn count_words_with_tokenizer(src: &str, tokenizer: &dyn Tokenizer) -> WordCounts {
+    tokenizer.tokenize(src)
+        .into_iter()
+        .fold(HashMap::new(), |mut acc, word| {
+            *acc.entry(word).or_insert(0) += 1;
+
+            acc
+        })
+}
+
+/// Aggregates word counts from [word_counts] into a single [WordCounts] result.
+pub fn aggregate_word_counts(word_counts: Vec<WordCounts>) -> WordCounts {
+    word_counts.into_iter()
+        .fold(HashMap::new(), |mut acc, result| {
+            for (word, result_count) in result {
+                let old_acc_count = match acc.get(&word) {
+                    Some(count) => *count,
+                    None => 0
+                };
+
+                acc.insert(word, old_acc_count + result_count);
+            }
+
+            acc
+        })
+}
+
+/// Sorts [word_counts] by [sort_order], comparing words by code point.
+///
+/// The result is the same on every run, no matter how [word_counts] is laid out.
+pub fn sort_word_counts(word_counts: &WordCounts, sort_order: SortOrder) -> Vec<(&String, &i32)> {
+    sort_word_counts_with_collation(word_counts, sort_order, Collation::CodePoint)
+}
+
+/// Sorts [word_counts] by [sort_order], comparing words using [collation].
+pub fn sort_word_counts_with_collation(word_counts: &WordCounts, sort_order: SortOrder, collation: Collation) -> Vec<(&String, &i32)> {
+    let mut kvps = word_counts
+        .iter()
+        .collect::<Vec<_>>();
+
+    kvps.sort_by(|a, b| match sort_order {
+        SortOrder::Ascending => a.1.cmp(b.1).then_with(|| collation.compare(a.0, b.0)),
+        SortOrder::Descending => b.1.cmp(a.1).then_with(|| collation.compare(a.0, b.0)),
+        SortOrder::Alphabetical => collation.compare(a.0, b.0),
+    });
+
+    kvps
+}
+
+/// Converts [word_counts] into [OrderedWordCounts].
+pub fn order_word_counts(word_counts: WordCounts) -> OrderedWordCounts {
+    word_counts.into_iter().collect()
+}
+
+#[cfg(test)]
+mod test {
+    use maplit::hashmap;
+
+    use super::*;
+    use crate::utils::*;
+
+    macro_rules! count_test {
+        ($input:expr, {$($key:expr => $value:expr),* $(,)?}) => {
+            assert_eq!(count_words($input.to_string()), stringify_map_keys(&hashmap!{
+                $($key => $value),*
+            }));
+        };
+    }
+
+    #[test]
+    fn count_words_can_count_simple_content() {
+        count_test!("hello world i am a str and i am proud", {
+            "hello" => 1,
+            "world" => 1,
+            "i" => 2,
+            "am" => 2,
+            "a" => 1,
+            "str"=> 1,
+            "and" => 1,
+            "proud" => 1
+        })
+    }
+
+    #[test]
+    fn count_words_strips_newlines_correctly() {
+        count_test!("hello world\ni am a\nstr and i am proud", {
+            "hello" => 1,
+            "world" => 1,
+            "i" => 2,
+            "am" => 2,
+            "a" => 1,
+            "str"=> 1,
+            "and" => 1,
+            "proud" => 1
+        });
+    }
+
+    #[test]
+    fn count_words_lowercases_words() {
+        count_test!("Hello World world hello", {
+            "hello" => 2,
+            "world" => 2,
+        });
+    }
+
+    #[test]
+    fn count_words_strips_commas() {
+        count_test!("Hello, world hello,", {
+            "hello" => 2,
+            "world" => 1,
+        });
+    }
+
+    #[test]
+    fn count_words_strips_dashes() {
+        count_test!("Hello - world -hello", { "hello" => 2, "world" => 1, });
+    }
+
+    #[test]
+    fn count_words_strips_periods() {
+        count_test!("Hello . world .hello", { "hello" => 2, "world" => 1, });
+    }
+
+    #[test]
+    fn count_words_strips_parens() {
+        count_test!("Hello ( ) (world )hello", { "hello" => 2, "world" => 1, });
+    }
+    
+    #[test]
+    fn count_words_strips_bangs() {
+        count_test!("Hello ! !world hello!", { "hello" => 2, "world" => 1, });
+    }
+
+    #[test]
+    fn count_words_strips_questions() {
+        count_test!("Hello ? ?world hello?", { "hello" => 2, "world" => 1, });
+    }
+
+    #[test]
+    fn count_words_keeps_non_ascii_words() {
+        count_test!("Canción, canción; “canción”", { "canción" => 3 });
+    }
+
+    #[test]
+    fn count_words_strips_em_dashes_and_ellipses() {
+        count_test!("Hello—world… hello...", { "hello" => 2, "world" => 1, });
+    }
+
+    #[test]
+    fn count_words_with_options_groups_word_forms() {
+        let options = WordCountOptionsBuilder::default()
+            .expand_contractions(true)
+            .lemmatize(true)
+            .stemming(Some(Language::English))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            count_words_with_options("I'm gonna go, going, gone; she goes", &options),
+            stringify_map_keys(&hashmap!{ "i" => 1, "be" => 1, "go" => 5, "to" => 1, "she" => 1 }));
+    }
+
+    #[test]
+    fn count_words_with_options_filters_stop_words_before_stemming() {
+        let options = WordCountOptionsBuilder::default()
+            .expand_contractions(true)
+            .stop_words(Some(StopWords::for_language(Language::English)))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            count_words_with_options("I'm wanna dance", &options),
+            stringify_map_keys(&hashmap!{ "want" => 1, "dance" => 1 }));
+    }
+
+    #[test]
+    fn aggregate_word_counts_sums_counts() {
+        let word_counts = vec![
+            stringify_map_keys(&hashmap!{ "hello" => 1, "world" => 2 }),
+            stringify_map_keys(&hashmap!{ "hello" => 3 }),
+        ];
+
+        assert_eq!(aggregate_word_counts(word_counts), stringify_map_keys(&hashmap!{ "hello" => 4, "world" => 2 }));
+    }
+
+    #[test]
+    fn count_words_with_options_uses_the_detected_language() {
+        let options = WordCountOptionsBuilder::default()
+            .language_stop_words(true)
+            .language_stemming(true)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            count_words_with_options("Las canciones de la casa, la canción del fuego", &options),
+            stringify_map_keys(&hashmap!{ "canciones" => 2, "casa" => 1, "fuego" => 1 }));
+    }
+
+    #[test]
+    fn count_words_with_options_tokenizes_the_detected_language() {
+        let options = WordCountOptionsBuilder::default()
+            .language_stop_words(true)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            count_words_with_options("L'amour de ma vie, c'est l'amour qu'il me donne, et j'attends l'été", &options),
+            stringify_map_keys(&hashmap!{ "amour" => 2, "vie" => 1, "donne" => 1, "attends" => 1 }));
+    }
+
+    #[test]
+    fn count_words_with_options_skips_language_steps_when_detection_is_unreliable() {
+        let options = WordCountOptionsBuilder::default()
+            .language_stop_words(true)
+            .language_stemming(true)
+            .build()
+            .unwrap();
+
+        assert!(!detect_language("la casa").unwrap().reliable);
+        assert_eq!(
+            count_words_with_options("la casa", &options),
+            stringify_map_keys(&hashmap!{ "la" => 1, "casa" => 1 }));
+    }
+
+    #[test]
+    fn sort_word_counts_descending_sorts_descending() {
+        let word_counts = stringify_map_keys(&hashmap!{ "hello" => 1, "world" => 2 });
+        let result = sort_word_counts(&word_counts, SortOrder::Descending);
+
+        assert_eq!(result, vec![(&"world".to_string(), &2i32), (&"hello".to_string(), &1i32)])
+    }
+
+    #[test]
+    fn sort_word_counts_ascending_sorts_ascending() {
+        let word_counts = stringify_map_keys(&hashmap!{ "hello" => 2, "world" => 1 });
+        let result = sort_word_counts(&word_counts, SortOrder::Ascending);
+
+        assert_eq!(result, vec![(&"world".to_string(), &1i32), (&"hello".to_string(), &2i32)])
+    }
+
+    #[test]
+    fn sort_word_counts_alphabetical_sorts_by_word() {
+        let word_counts = stringify_map_keys(&hashmap!{ "world" => 2, "hello" => 1, "fire" => 3 });
+        let result = sort_word_counts(&word_counts, SortOrder::Alphabetical);
+
+        assert_eq!(result, vec![(&"fire".to_string(), &3i32), (&"hello".to_string(), &1i32), (&"world".to_string(), &2i32)])
+    }
+
+    #[test]
+    fn sort_word_counts_with_collation_uses_collation() {
+        let word_counts = stringify_map_keys(&hashmap!{ "Zebra" => 1, "éclair" => 1, "apple" => 2 });
+
+        assert_eq!(
+            sort_word_counts_with_collation(&word_counts, SortOrder::Alphabetical, Collation::Natural),
+            vec![(&"apple".to_string(), &2i32), (&"éclair".to_string(), &1i32), (&"Zebra".to_string(), &1i32)]);
+        assert_eq!(
+            sort_word_counts_with_collation(&word_counts, SortOrder::Descending, Collation::CodePoint),
+            vec![(&"apple".to_string(), &2i32), (&"Zebra".to_string(), &1i32), (&"éclair".to_string(), &1i32)]);
+    }
+
+    #[test]
+    fn sort_word_counts_is_deterministic() {
+        let words = (0..100).map(|i| format!("word{}", i)).collect::<Vec<_>>();
+        let word_counts = words.iter().map(|word| (word.clone(), 1)).collect::<WordCounts>();
+        let reversed = words.iter().rev().map(|word| (word.clone(), 1)).collect::<WordCounts>();
+
+        assert_eq!(sort_word_counts(&word_counts, SortOrder::Descending), sort_word_counts(&reversed, SortOrder::Descending));
+    }
+
+    #[test]
+    fn order_word_counts_orders_by_word() {
+        let ordered = order_word_counts(stringify_map_keys(&hashmap!{ "world" => 1, "hello" => 2 }));
+
+        assert_eq!(ordered.into_iter().collect::<Vec<_>>(), vec![("hello".to_string(), 2), ("world".to_string(), 1)]);
+    }
+
+    #[test]
+    fn sort_word_counts_breaks_ties_alphabetically() {
+        let word_counts = stringify_map_keys(&hashmap!{ "world" => 1, "hello" => 1, "fire" => 2 });
+
+        assert_eq!(
+            sort_word_counts(&word_counts, SortOrder::Descending),
+            vec![(&"fire".to_string(), &2i32), (&"hello".to_string(), &1i32), (&"world".to_string(), &1i32)]);
+        assert_eq!(
+            sort_word_counts(&word_counts, SortOrder::Ascending),
+            vec![(&"hello".to_string(), &1i32), (&"world".to_string(), &1i32), (&"fire".to_string(), &2i32)]);
+    }
+}
\ No newline at end of file
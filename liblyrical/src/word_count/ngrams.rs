@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+
+use super::*;
+
+/// Counts the phrases of [n] consecutive words in [src] ("let me go" for `n = 3`),
+/// keyed by their space-separated words.
+///
+/// Phrases never span lines, since the last words of one lyric line and the first
+/// of the next usually don't belong together. Words are normalized according to
+/// [options], except that stop words are never removed from inside a phrase (that
+/// would stitch together words that weren't next to each other); phrases made up
+/// only of stop words are dropped instead. Stemming doesn't apply to phrases.
+pub fn count_ngrams(src: &str, n: usize, options: &WordCountOptions) -> WordCounts {
+    if n == 0 {
+        return HashMap::new();
+    }
+
+    let options = options.for_text(src);
+    let tokenizer = UnicodeTokenizer::new(options.tokenizer.clone());
+    let lemmatizer = match options.lemmatize {
+        true => Some(Lemmatizer::new()),
+        false => None
+    };
+
+    src.lines()
+        .flat_map(|line| {
+            let mut words = tokenizer.tokenize(line);
+
+            if options.expand_contractions {
+                words = expand_contractions(words);
+            }
+
+            if let Some(lemmatizer) = &lemmatizer {
+                words = words.into_iter()
+                    .map(|word| lemmatizer.lemmatize(&word))
+                    .collect();
+            }
+
+            words.windows(n)
+                .filter(|phrase| match &options.stop_words {
+                    Some(stop_words) => !phrase.iter().all(|word| stop_words.contains(word)),
+                    None => true
+                })
+                .map(|phrase| phrase.join(" "))
+                .collect::<Vec<_>>()
+        })
+        .fold(HashMap::new(), |mut acc, phrase| {
+            *acc.entry(phrase).or_insert(0) += 1;
+
+            acc
+        })
+}
+
+/// Counts the phrases of each length in [sizes] in [src] (e.g. `&[2, 3]` for
+/// bigrams and trigrams) as one [WordCounts]; see [count_ngrams].
+pub fn count_phrases(src: &str, sizes: &[usize], options: &WordCountOptions) -> WordCounts {
+    sizes.iter()
+        .flat_map(|n| count_ngrams(src, *n, options))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::*;
+
+    #[test]
+    fn count_ngrams_counts_bigrams() {
+        assert_eq!(
+            count_ngrams("Let me go, let me go", 2, &WordCountOptions::default()),
+            stringify_map_keys(&hashmap!{ "let me" => 2, "me go" => 2, "go let" => 1 }));
+    }
+
+    #[test]
+    fn count_ngrams_does_not_span_lines() {
+        assert_eq!(
+            count_ngrams("House of fire\nHouse of fire\n\nfire", 3, &WordCountOptions::default()),
+            stringify_map_keys(&hashmap!{ "house of fire" => 2 }));
+    }
+
+    #[test]
+    fn count_ngrams_drops_phrases_made_only_of_stop_words() {
+        let options = WordCountOptionsBuilder::default()
+            .stop_words(Some(StopWords::for_language(Language::English)))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            count_ngrams("Welcome to the house of fire", 2, &options),
+            stringify_map_keys(&hashmap!{ "welcome to" => 1, "the house" => 1, "house of" => 1, "of fire" => 1 }));
+    }
+
+    #[test]
+    fn count_ngrams_ignores_zero_and_overlong_sizes() {
+        assert!(count_ngrams("let me go", 0, &WordCountOptions::default()).is_empty());
+        assert!(count_ngrams("let me go", 4, &WordCountOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn count_phrases_merges_sizes() {
+        assert_eq!(
+            count_phrases("I'm gonna go", &[2, 3], &WordCountOptionsBuilder::default().expand_contractions(true).build().unwrap()),
+            stringify_map_keys(&hashmap!{
+                "i am" => 1, "am going" => 1, "going to" => 1, "to go" => 1,
+                "i am going" => 1, "am going to" => 1, "going to go" => 1,
+            }));
+    }
+}
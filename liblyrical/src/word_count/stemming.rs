@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use rust_stemmers::{Algorithm, Stemmer};
+
+use super::*;
+
+/// Parses a bundled `form=replacement` table, skipping comments and blank lines.
+fn parse_table(table: &'static str) -> HashMap<&'static str, &'static str> {
+    table.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut parts = line.splitn(2, '=');
+
+            Some((parts.next()?.trim(), parts.next()?.trim()))
+        })
+        .collect()
+}
+
+/// Expands contractions and informal spellings into the words they stand for
+/// ("i'm" => "i am", "wanna" => "want to", "runnin'" => "running").
+///
+/// Only English contractions are known; everything else is left as-is.
+pub fn expand_contractions(words: Vec<String>) -> Vec<String> {
+    let contractions = parse_table(include_str!("../../data/contractions/en.txt"));
+
+    words.into_iter()
+        .flat_map(|word| {
+            let expanded = match contractions.get(word.as_str()) {
+                Some(expansion) => expansion.to_string(),
+                // Restore dropped g's ("runnin'" => "running").
+                None if word.ends_with("in'") => format!("{}g", word.trim_end_matches('\'')),
+                None => word
+            };
+
+            expanded.split(' ')
+                .map(|word| word.to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Maps irregular English word forms to their lemma ("went" => "go", "was" => "be").
+#[derive(Clone, Debug)]
+pub struct Lemmatizer {
+    lemmas: HashMap<&'static str, &'static str>,
+}
+
+impl Lemmatizer {
+    pub fn new() -> Self {
+        Lemmatizer { lemmas: parse_table(include_str!("../../data/lemmas/en.txt")) }
+    }
+
+    pub fn lemmatize(&self, word: &str) -> String {
+        match self.lemmas.get(word) {
+            Some(lemma) => lemma.to_string(),
+            None => word.to_string()
+        }
+    }
+}
+
+impl Default for Lemmatizer {
+    fn default() -> Self {
+        Lemmatizer::new()
+    }
+}
+
+/// A Snowball stemmer for one of the supported [Language]s ("going" => "go").
+pub struct WordStemmer {
+    language: Language,
+    stemmer: Stemmer,
+}
+
+impl WordStemmer {
+    pub fn new(language: Language) -> Self {
+        let algorithm = match language {
+            Language::English => Algorithm::English,
+            Language::Spanish => Algorithm::Spanish,
+            Language::French => Algorithm::French,
+            Language::German => Algorithm::German,
+            Language::Portuguese => Algorithm::Portuguese,
+            Language::Italian => Algorithm::Italian,
+        };
+
+        WordStemmer { language, stemmer: Stemmer::create(algorithm) }
+    }
+
+    pub fn stem(&self, word: &str) -> String {
+        self.stemmer.stem(word).to_string()
+    }
+}
+
+impl Debug for WordStemmer {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        write!(formatter, "WordStemmer {{ language: {:?} }}", self.language)
+    }
+}
+
+/// Merges the words in [word_counts] that map to the same [key] (e.g. the same
+/// stem); each group is labeled by its most common word so results stay readable.
+pub fn group_word_forms<F: Fn(&str) -> String>(word_counts: WordCounts, key: F) -> WordCounts {
+    let groups = word_counts.into_iter()
+        .fold(HashMap::new(), |mut acc: HashMap<String, Vec<(String, i32)>>, (word, count)| {
+            acc.entry(key(&word)).or_default().push((word, count));
+
+            acc
+        });
+
+    groups.into_values()
+        .map(|forms| {
+            let total = forms.iter().map(|(_, count)| count).sum();
+
+            // Label the group with its most common form, breaking ties alphabetically.
+            let label = forms.into_iter()
+                .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+                .map(|(word, _)| word)
+                .unwrap();
+
+            (label, total)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::*;
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn expand_contractions_expands_known_contractions() {
+        assert_eq!(
+            expand_contractions(words(&["i'm", "gonna", "wanna", "'cause", "fire"])),
+            words(&["i", "am", "going", "to", "want", "to", "because", "fire"]));
+    }
+
+    #[test]
+    fn expand_contractions_restores_dropped_gs() {
+        assert_eq!(expand_contractions(words(&["runnin'", "in"])), words(&["running", "in"]));
+    }
+
+    #[test]
+    fn lemmatizer_maps_irregular_forms() {
+        let lemmatizer = Lemmatizer::new();
+
+        assert_eq!(lemmatizer.lemmatize("went"), "go");
+        assert_eq!(lemmatizer.lemmatize("gone"), "go");
+        assert_eq!(lemmatizer.lemmatize("fire"), "fire");
+    }
+
+    #[test]
+    fn word_stemmer_stems_per_language() {
+        assert_eq!(WordStemmer::new(Language::English).stem("going"), "go");
+        assert_eq!(WordStemmer::new(Language::Spanish).stem("canciones"), "cancion");
+    }
+
+    #[test]
+    fn group_word_forms_merges_and_labels_by_most_common_form() {
+        let stemmer = WordStemmer::new(Language::English);
+        let word_counts = stringify_map_keys(&hashmap!{ "dance" => 3, "dancing" => 2, "dances" => 1, "fire" => 1 });
+
+        assert_eq!(
+            group_word_forms(word_counts, |word| stemmer.stem(word)),
+            stringify_map_keys(&hashmap!{ "dance" => 6, "fire" => 1 }));
+    }
+}
\ No newline at end of file
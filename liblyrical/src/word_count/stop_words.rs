@@ -0,0 +1,206 @@
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::Read;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// Languages we ship built-in word lists for.
+///
+/// Serialized as its ISO 639-1 code, but either the code or the name is accepted.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub enum Language {
+    English,
+    Spanish,
+    French,
+    German,
+    Portuguese,
+    Italian,
+}
+
+impl Language {
+    pub const ALL: [Language; 6] = [
+        Language::English,
+        Language::Spanish,
+        Language::French,
+        Language::German,
+        Language::Portuguese,
+        Language::Italian,
+    ];
+
+    /// The ISO 639-1 code for the language.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Spanish => "es",
+            Language::French => "fr",
+            Language::German => "de",
+            Language::Portuguese => "pt",
+            Language::Italian => "it",
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    /// Parses either an ISO 639-1 code ("en") or an English name ("english").
+    fn from_str(language: &str) -> Result<Self, Self::Err> {
+        let language = language.trim().to_lowercase();
+
+        Language::ALL.iter()
+            .find(|candidate| {
+                candidate.code() == language
+                    || format!("{:?}", candidate).to_lowercase() == language
+            })
+            .cloned()
+            .ok_or_else(|| format!(
+                "Unknown language \"{}\"; expected one of: {}",
+                language,
+                Language::ALL.iter().map(|language| language.code()).collect::<Vec<_>>().join(", ")))
+    }
+}
+
+impl TryFrom<String> for Language {
+    type Error = String;
+
+    fn try_from(language: String) -> Result<Self, Self::Error> {
+        language.parse()
+    }
+}
+
+impl From<Language> for String {
+    fn from(language: Language) -> Self {
+        language.code().to_string()
+    }
+}
+
+/// A set of words that should be ignored when counting words.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StopWords {
+    words: HashSet<String>,
+}
+
+impl StopWords {
+    pub fn new() -> Self {
+        StopWords::default()
+    }
+
+    /// Makes [StopWords] from the built-in list for [language].
+    pub fn for_language(language: Language) -> Self {
+        let list = match language {
+            Language::English => include_str!("../../data/stop_words/en.txt"),
+            Language::Spanish => include_str!("../../data/stop_words/es.txt"),
+            Language::French => include_str!("../../data/stop_words/fr.txt"),
+            Language::German => include_str!("../../data/stop_words/de.txt"),
+            Language::Portuguese => include_str!("../../data/stop_words/pt.txt"),
+            Language::Italian => include_str!("../../data/stop_words/it.txt"),
+        };
+
+        StopWords::from_list(list)
+    }
+
+    /// Makes [StopWords] from the built-in lists for all of [languages].
+    pub fn for_languages(languages: &[Language]) -> Self {
+        languages.iter()
+            .fold(StopWords::new(), |mut acc, language| {
+                acc.extend(StopWords::for_language(*language));
+
+                acc
+            })
+    }
+
+    /// Makes [StopWords] from [words]; they're lowercased to match [count_words].
+    pub fn from_words<I: IntoIterator<Item = S>, S: AsRef<str>>(words: I) -> Self {
+        StopWords {
+            words: words.into_iter()
+                .map(|word| word.as_ref().trim().to_lowercase())
+                .filter(|word| !word.is_empty())
+                .collect()
+        }
+    }
+
+    /// Makes [StopWords] from a newline-separated list; lines starting with `#` are
+    /// treated as comments.
+    pub fn from_list(list: &str) -> Self {
+        StopWords::from_words(list.lines().filter(|line| !line.trim_start().starts_with('#')))
+    }
+
+    /// Reads a newline-separated list of stop words from the file at [path].
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let mut list = String::new();
+
+        File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut list))
+            .map_err(|err| format!("Failed to read stop words from {}: {}", path, err))?;
+
+        Ok(StopWords::from_list(&list))
+    }
+
+    pub fn extend(&mut self, other: StopWords) {
+        self.words.extend(other.words);
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(word)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+/// Removes every word in [stop_words] from [word_counts].
+pub fn filter_stop_words(word_counts: WordCounts, stop_words: &StopWords) -> WordCounts {
+    word_counts.into_iter()
+        .filter(|(word, _)| !stop_words.contains(word))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::*;
+
+    #[test]
+    fn language_parses_codes_and_names() {
+        assert_eq!("en".parse::<Language>(), Ok(Language::English));
+        assert_eq!("Spanish".parse::<Language>(), Ok(Language::Spanish));
+        assert!("klingon".parse::<Language>().is_err());
+    }
+
+    #[test]
+    fn language_serializes_as_code() {
+        assert_eq!(serde_json::to_string(&Language::German).unwrap(), "\"de\"");
+        assert_eq!(serde_json::from_str::<Language>("\"german\"").unwrap(), Language::German);
+    }
+
+    #[test]
+    fn stop_words_has_built_in_lists() {
+        let stop_words = StopWords::for_languages(&[Language::English, Language::Spanish]);
+
+        assert!(stop_words.contains("the"));
+        assert!(stop_words.contains("i'm"));
+        assert!(stop_words.contains("porque"));
+        assert!(!stop_words.contains("fire"));
+    }
+
+    #[test]
+    fn stop_words_from_list_skips_comments_and_blanks() {
+        let stop_words = StopWords::from_list("# my list\nFoo\n\n  bar  \n");
+
+        assert_eq!(stop_words, StopWords::from_words(vec!["foo", "bar"]));
+    }
+
+    #[test]
+    fn filter_stop_words_removes_stop_words() {
+        let word_counts = count_words("The house of fire, let me go to the house".to_string());
+        let filtered = filter_stop_words(word_counts, &StopWords::for_language(Language::English));
+
+        assert_eq!(filtered, stringify_map_keys(&hashmap!{ "house" => 2, "fire" => 1, "let" => 1, "go" => 1 }));
+    }
+}
\ No newline at end of file
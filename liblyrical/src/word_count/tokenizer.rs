@@ -0,0 +1,232 @@
+use std::fmt::Debug;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::Language;
+
+/// Words that are commonly written with a leading apostrophe to mark dropped letters
+/// ("'cause", "'til") and should keep it.
+const LEADING_ELISIONS: [&str; 9] = ["cause", "cos", "cuz", "til", "em", "bout", "round", "tis", "twas"];
+
+/// French words that drop their last vowel before a vowel ("l'amour", "qu'il") and
+/// should be counted apart from the word they're attached to.
+const FRENCH_ELIDED_WORDS: [&str; 12] = ["c", "d", "j", "l", "m", "n", "s", "t", "qu", "jusqu", "lorsqu", "puisqu"];
+
+/// Italian words that drop their last vowel before a vowel ("l'amore", "dell'anima").
+const ITALIAN_ELIDED_WORDS: [&str; 14] = ["c", "d", "l", "m", "n", "s", "t", "v", "un", "all", "dall", "dell", "nell", "sull"];
+
+/// Splits text into the words that should be counted.
+pub trait Tokenizer: Debug {
+    fn tokenize(&self, text: &str) -> Vec<String>;
+}
+
+#[derive(Clone, Builder, Debug)]
+#[builder(setter(into))]
+pub struct UnicodeTokenizerOptions {
+    /// Whether tokens should be lowercased.
+    #[builder(default = "true")]
+    lowercase: bool,
+    /// Whether purely numeric tokens ("1999", "3.14") should be kept.
+    #[builder(default = "true")]
+    include_numbers: bool,
+    /// Whether apostrophes marking dropped letters ("'cause", "runnin'") should be kept.
+    #[builder(default = "true")]
+    keep_elisions: bool,
+    /// The language of the text, if known; English elisions ("'cause", "runnin'")
+    /// are only looked for in English (or unknown) text, and French and Italian
+    /// elided words ("l'amour") are split off the word they're attached to.
+    #[builder(default)]
+    language: Option<Language>,
+}
+
+impl UnicodeTokenizerOptions {
+    /// These options for text in [language], unless a language was already set.
+    pub(crate) fn for_language(&self, language: Language) -> Self {
+        UnicodeTokenizerOptions { language: self.language.or(Some(language)), ..self.clone() }
+    }
+}
+
+impl Default for UnicodeTokenizerOptions {
+    fn default() -> Self {
+        UnicodeTokenizerOptionsBuilder::default().build().unwrap()
+    }
+}
+
+/// A [Tokenizer] that finds words using Unicode word boundaries (UAX #29), so
+/// accented, non-latin and CJK text is handled, and quotes, dashes and ellipses
+/// never end up attached to words.
+#[derive(Clone, Debug, Default)]
+pub struct UnicodeTokenizer {
+    options: UnicodeTokenizerOptions,
+}
+
+impl UnicodeTokenizer {
+    pub fn new(options: UnicodeTokenizerOptions) -> Self {
+        UnicodeTokenizer { options }
+    }
+}
+
+impl Tokenizer for UnicodeTokenizer {
+    fn tokenize(&self, text: &str) -> Vec<String> {
+        // Curly apostrophes would otherwise make "don’t" and "don't" different words.
+        let text = text.replace(['\u{2019}', '\u{2018}', '\u{02BC}'], "'");
+        let segments = text.split_word_bounds().collect::<Vec<_>>();
+
+        segments.iter()
+            .enumerate()
+            .filter(|(_, segment)| is_word(segment))
+            .filter(|(_, segment)| self.options.include_numbers || segment.chars().any(char::is_alphabetic))
+            .map(|(i, segment)| {
+                let mut word = segment.to_string();
+
+                if self.options.keep_elisions && has_english_elisions(self.options.language) {
+                    if has_leading_elision(&segments, i) {
+                        word.insert(0, '\'');
+                    } else if has_trailing_elision(&segments, i) {
+                        word.push('\'');
+                    }
+                }
+
+                match self.options.lowercase {
+                    true => word.to_lowercase(),
+                    false => word
+                }
+            })
+            .flat_map(|word| split_elided_word(word, self.options.language))
+            .collect()
+    }
+}
+
+/// Whether words in [language] can have elisions like "'cause" and "runnin'".
+fn has_english_elisions(language: Option<Language>) -> bool {
+    matches!(language, None | Some(Language::English))
+}
+
+/// Splits [word] into an elided word and the word it's attached to if it's
+/// something like "l'amour" in [language]; the elided word keeps its apostrophe.
+fn split_elided_word(mut word: String, language: Option<Language>) -> Vec<String> {
+    let elided_words: &[&str] = match language {
+        Some(Language::French) => &FRENCH_ELIDED_WORDS,
+        Some(Language::Italian) => &ITALIAN_ELIDED_WORDS,
+        _ => return vec![word]
+    };
+
+    match word.find('\'') {
+        Some(i) if i + 1 < word.len() && elided_words.contains(&word[..i].to_lowercase().as_str()) => {
+            let rest = word.split_off(i + 1);
+
+            vec![word, rest]
+        },
+        _ => vec![word]
+    }
+}
+
+fn is_word(segment: &str) -> bool {
+    segment.chars().any(char::is_alphanumeric)
+}
+
+fn is_apostrophe(segment: Option<&&str>) -> bool {
+    segment == Some(&"'")
+}
+
+/// Whether the word at [i] is something like "'cause" (an apostrophe at the start of
+/// a word followed by a known elision).
+fn has_leading_elision(segments: &[&str], i: usize) -> bool {
+    let starts_word = i < 2 || !is_word(segments[i - 2]);
+
+    i >= 1
+        && starts_word
+        && is_apostrophe(segments.get(i - 1))
+        && LEADING_ELISIONS.contains(&segments[i].to_lowercase().as_str())
+}
+
+/// Whether the word at [i] is something like "runnin'" (a dropped "g" marked by an
+/// apostrophe at the end of the word).
+fn has_trailing_elision(segments: &[&str], i: usize) -> bool {
+    let ends_word = segments.get(i + 2).map(|segment| !is_word(segment)).unwrap_or(true);
+    let word = segments[i].to_lowercase();
+
+    ends_word
+        && is_apostrophe(segments.get(i + 1))
+        && word.chars().count() > 3
+        && word.ends_with("in")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn tokenize(text: &str) -> Vec<String> {
+        UnicodeTokenizer::default().tokenize(text)
+    }
+
+    #[test]
+    fn unicode_tokenizer_keeps_accented_words() {
+        assert_eq!(tokenize("Canción de la vida, ça va? Schöne Grüße"), vec!["canción", "de", "la", "vida", "ça", "va", "schöne", "grüße"]);
+    }
+
+    #[test]
+    fn unicode_tokenizer_handles_non_latin_scripts() {
+        assert_eq!(tokenize("トウキョウ Москва"), vec!["トウキョウ", "москва"]);
+    }
+
+    #[test]
+    fn unicode_tokenizer_strips_curly_quotes() {
+        assert_eq!(tokenize("“hello” ‘hello’ \"hello\""), vec!["hello", "hello", "hello"]);
+    }
+
+    #[test]
+    fn unicode_tokenizer_normalizes_curly_apostrophes() {
+        assert_eq!(tokenize("don’t don't"), vec!["don't", "don't"]);
+    }
+
+    #[test]
+    fn unicode_tokenizer_splits_on_em_dashes_and_ellipses() {
+        assert_eq!(tokenize("fire—let go…go...go"), vec!["fire", "let", "go", "go", "go"]);
+    }
+
+    #[test]
+    fn unicode_tokenizer_keeps_elisions() {
+        assert_eq!(tokenize("'Cause I'm runnin' 'round"), vec!["'cause", "i'm", "runnin'", "'round"]);
+    }
+
+    #[test]
+    fn unicode_tokenizer_can_drop_elisions() {
+        let options = UnicodeTokenizerOptionsBuilder::default().keep_elisions(false).build().unwrap();
+
+        assert_eq!(UnicodeTokenizer::new(options).tokenize("'cause runnin'"), vec!["cause", "runnin"]);
+    }
+
+    #[test]
+    fn unicode_tokenizer_splits_elided_words_in_french_and_italian() {
+        let tokenize_in = |language, text| {
+            let options = UnicodeTokenizerOptionsBuilder::default().language(Some(language)).build().unwrap();
+
+            UnicodeTokenizer::new(options).tokenize(text)
+        };
+
+        assert_eq!(tokenize_in(Language::French, "L'amour qu'il aujourd'hui"), vec!["l'", "amour", "qu'", "il", "aujourd'hui"]);
+        assert_eq!(tokenize_in(Language::Italian, "dell'anima"), vec!["dell'", "anima"]);
+        assert_eq!(tokenize_in(Language::French, "'cause"), vec!["cause"]);
+        assert_eq!(tokenize_in(Language::English, "l'amour 'cause"), vec!["l'amour", "'cause"]);
+    }
+
+    #[test]
+    fn unicode_tokenizer_keeps_numbers_whole() {
+        assert_eq!(tokenize("1,000 ways in 1999"), vec!["1,000", "ways", "in", "1999"]);
+    }
+
+    #[test]
+    fn unicode_tokenizer_can_drop_numbers() {
+        let options = UnicodeTokenizerOptionsBuilder::default().include_numbers(false).build().unwrap();
+
+        assert_eq!(UnicodeTokenizer::new(options).tokenize("1,000 ways in 1999"), vec!["ways", "in"]);
+    }
+
+    #[test]
+    fn unicode_tokenizer_can_preserve_case() {
+        let options = UnicodeTokenizerOptionsBuilder::default().lowercase(false).build().unwrap();
+
+        assert_eq!(UnicodeTokenizer::new(options).tokenize("Hello World"), vec!["Hello", "World"]);
+    }
+}
\ No newline at end of file
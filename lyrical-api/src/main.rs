@@ -6,8 +6,11 @@ extern crate warp;
 use std::collections::HashMap;
 use std::net::SocketAddr;
 
+use liblyrical::analysis;
+use liblyrical::analysis::{Explicitness, LyricsAnalysis, ProfanityFilter, SentimentAnalyzer, SongSentiment};
 use liblyrical::lyrics::{LyricsFetcher, SongDescriptor};
 use liblyrical::word_count;
+use liblyrical::word_count::{DetectedLanguage, Language, OrderedWordCounts, StopWords, WordCountOptionsBuilder};
 use serde::{Deserialize, Serialize};
 use warp::Filter;
 
@@ -16,17 +19,115 @@ const SERVER_ADDR: &'static str = "127.0.0.1:8080";
 #[derive(Serialize, Deserialize)]
 struct GetLyricalFrequencyRequest {
     pub songs: Vec<SongDescriptor>,
+    #[serde(default)]
+    pub stop_words: Option<StopWordsRequest>,
+    /// Whether contractions like "i'm" should be counted as the words they stand for.
+    #[serde(default)]
+    pub expand_contractions: bool,
+    /// Whether irregular word forms like "went" should be counted as their lemma.
+    #[serde(default)]
+    pub lemmatize: bool,
+    /// The language whose stemmer should be used to group word forms together.
+    #[serde(default)]
+    pub stemming: Option<Language>,
+    /// Whether each song's detected language should pick the stemmer when [stemming] isn't set.
+    #[serde(default)]
+    pub stem_detected_language: bool,
+    /// Lengths of the phrases that should also be counted (e.g. [2, 3]).
+    #[serde(default)]
+    pub ngrams: Vec<usize>,
+    /// Whether vocabulary richness and repetition metrics should be included.
+    #[serde(default)]
+    pub analyze: bool,
+    #[serde(default)]
+    pub profanity: Option<ProfanityRequest>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StopWordsRequest {
+    /// Languages whose built-in stop words should be ignored (e.g. "en").
+    #[serde(default)]
+    pub languages: Vec<Language>,
+    /// Additional words to ignore.
+    #[serde(default)]
+    pub words: Vec<String>,
+    /// Whether the built-in stop words of each song's detected language should be ignored too.
+    #[serde(default)]
+    pub detected_language: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ProfanityRequest {
+    /// Additional words to treat as profane.
+    #[serde(default)]
+    pub words: Vec<String>,
+    /// Whether word counts, phrases and analyses should be left out for explicit songs.
+    #[serde(default)]
+    pub exclude_explicit: bool,
+    /// Whether profane words should be masked in word and phrase counts.
+    #[serde(default)]
+    pub mask: bool,
+}
+
+impl ProfanityRequest {
+    fn to_profanity_filter(&self) -> ProfanityFilter {
+        let mut profanity_filter = ProfanityFilter::new();
+        profanity_filter.extend(ProfanityFilter::from_words(&self.words));
+
+        profanity_filter
+    }
+}
+
+impl StopWordsRequest {
+    fn to_stop_words(&self) -> StopWords {
+        let mut stop_words = StopWords::for_languages(&self.languages);
+        stop_words.extend(StopWords::from_words(&self.words));
+
+        stop_words
+    }
 }
 
 #[derive(Serialize, Deserialize)]
 struct GetLyricalFrequencyResponse {
     pub results: Vec<GetLyricalFrequencyResponseResult>,
+    /// Metrics for all of the songs whose lyrics were found, if requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub analysis: Option<LyricsAnalysis>,
 }
 
 #[derive(Serialize, Deserialize)]
 struct GetLyricalFrequencyResponseResult {
     pub song: SongDescriptor,
-    pub frequencies: Option<HashMap<String, i32>>
+    /// Word frequencies, ordered by word.
+    pub frequencies: Option<OrderedWordCounts>,
+    /// How explicit the song is, if profanity checks were requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explicitness: Option<Explicitness>,
+    /// The language the song's lyrics were detected to be in.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<DetectedLanguage>,
+    /// Phrase frequencies ordered by phrase, if any n-gram sizes were requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phrases: Option<OrderedWordCounts>,
+    /// Metrics for the song, if requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub analysis: Option<LyricsAnalysis>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GetSentimentRequest {
+    pub songs: Vec<SongDescriptor>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GetSentimentResponse {
+    pub results: Vec<GetSentimentResponseResult>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GetSentimentResponseResult {
+    pub song: SongDescriptor,
+    pub sentiment: Option<SongSentiment>,
 }
 
 #[tokio::main]
@@ -38,16 +139,96 @@ async fn main() {
         .and(warp::body::json())
         .map(|req: GetLyricalFrequencyRequest| {
             let mut fetcher = liblyrical::lyrics::make_lyrics_fetcher();
+            let options = WordCountOptionsBuilder::default()
+                .language_stop_words(req.stop_words.as_ref().map(|stop_words| stop_words.detected_language).unwrap_or(false))
+                .stop_words(req.stop_words.map(|stop_words| stop_words.to_stop_words()))
+                .expand_contractions(req.expand_contractions)
+                .lemmatize(req.lemmatize)
+                .stemming(req.stemming)
+                .language_stemming(req.stem_detected_language)
+                .build()
+                .unwrap();
+
+            let ngrams = req.ngrams;
+            let analyze = req.analyze;
+            let profanity_filter = req.profanity.as_ref().map(|profanity| profanity.to_profanity_filter());
+            let exclude_explicit = req.profanity.as_ref().map(|profanity| profanity.exclude_explicit).unwrap_or(false);
+            let mask_profanity = req.profanity.as_ref().map(|profanity| profanity.mask).unwrap_or(false);
+
+            let lyrics = req.songs.into_iter()
+                .map(|song| {
+                    let lyrics = fetcher.fetch_lyrics(&song).ok();
+
+                    let explicitness = match (&profanity_filter, &lyrics) {
+                        (Some(profanity_filter), Some(lyrics)) =>
+                            Some(profanity_filter.explicitness(&word_count::count_words(lyrics.clone()))),
+                        _ => None
+                    };
+
+                    // Excluded songs are only reported with their explicitness.
+                    let is_excluded = exclude_explicit && explicitness.as_ref().map(|explicitness| explicitness.explicit).unwrap_or(false);
+                    let lyrics = lyrics.filter(|_| !is_excluded);
+
+                    (song, lyrics, explicitness)
+                })
+                .collect::<Vec<_>>();
+
+            let mask = |word_counts: HashMap<String, i32>| match &profanity_filter {
+                Some(profanity_filter) if mask_profanity => profanity_filter.mask_word_counts(word_counts),
+                _ => word_counts
+            };
+
+            let collection_analysis = match analyze {
+                true => Some(analysis::analyze_collection(&lyrics.iter()
+                    .filter_map(|(_, lyrics, _)| lyrics.as_deref())
+                    .collect::<Vec<_>>())),
+                false => None
+            };
 
             let response = GetLyricalFrequencyResponse {
+                results: lyrics.into_iter()
+                    .map(|(song, lyrics, explicitness)| {
+                        let frequencies = lyrics.as_ref()
+                            .map(|lyrics| word_count::order_word_counts(mask(word_count::count_words_with_options(lyrics, &options))));
+
+                        let language = lyrics.as_ref()
+                            .and_then(|lyrics| word_count::detect_language(lyrics));
+
+                        let phrases = lyrics.as_ref()
+                            .filter(|_| !ngrams.is_empty())
+                            .map(|lyrics| word_count::order_word_counts(mask(word_count::count_phrases(lyrics, &ngrams, &options))));
+
+                        let analysis = lyrics.as_ref()
+                            .filter(|_| analyze)
+                            .map(|lyrics| analysis::analyze_lyrics(lyrics));
+
+                        GetLyricalFrequencyResponseResult { song, frequencies, explicitness, language, phrases, analysis }
+                    })
+                    .collect(),
+                analysis: collection_analysis,
+            };
+
+            warp::reply::json(&response)
+        });
+
+    // POST /sentiment
+    // Synchronously scores the sentiment of songs and each of their lines.
+    let sentiment = warp::path!("sentiment")
+        .and(warp::post())
+        .and(warp::body::json())
+        .map(|req: GetSentimentRequest| {
+            let mut fetcher = liblyrical::lyrics::make_lyrics_fetcher();
+            let analyzer = SentimentAnalyzer::new();
+
+            let response = GetSentimentResponse {
                 results: req.songs.into_iter()
                     .map(|song| {
-                        let frequencies = fetcher
+                        let sentiment = fetcher
                             .fetch_lyrics(&song)
-                            .map(|lyrics| Some(word_count::count_words(lyrics)))
-                            .unwrap_or(None);
+                            .map(|lyrics| analyzer.analyze(&lyrics))
+                            .ok();
 
-                        GetLyricalFrequencyResponseResult { song, frequencies }
+                        GetSentimentResponseResult { song, sentiment }
                     })
                     .collect()
             };
@@ -63,9 +244,19 @@ async fn main() {
             .header("content-type", "application/json; charset=utf-8")
             .body(include_str!("../build_info.json")));
 
+    // GET /metrics
+    // Displays fetcher and cache metrics in the Prometheus text format.
+    let metrics = warp::path!("metrics")
+        .and(warp::get())
+        .map(|| warp::http::Response::builder()
+            .header("content-type", "text/plain; version=0.0.4; charset=utf-8")
+            .body(liblyrical::metrics::global().render_prometheus()));
+
     // Build all routes.
     let routes = word_frequency_sync
-        .or(build_info);
+        .or(sentiment)
+        .or(build_info)
+        .or(metrics);
 
     println!("starting lyrical-api on {}", SERVER_ADDR);
 
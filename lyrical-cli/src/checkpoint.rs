@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::fs;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use liblyrical::lyrics::{BeforeFetch, LyricsMiddleware, LyricsResult, SongDescriptor};
+
+/// Where runs record their progress unless told otherwise.
+pub const CHECKPOINT_LOCATION: &str = "./cache/checkpoint.ndjson";
+
+/// A line of a checkpoint file: a song and the lyrics fetched for it.
+#[derive(Serialize, Deserialize)]
+struct CheckpointEntry {
+    song: SongDescriptor,
+    lyrics: String,
+}
+
+/// A [LyricsMiddleware] that records each song's lyrics in a checkpoint file as
+/// soon as they're fetched so an interrupted run can be resumed, answering songs
+/// that are already in the checkpoint from there. Songs that couldn't be fetched
+/// aren't recorded, so resuming tries them again.
+#[derive(Debug)]
+pub struct CheckpointMiddleware {
+    file: File,
+    entries: HashMap<SongDescriptor, String>,
+}
+
+impl CheckpointMiddleware {
+    /// Opens the checkpoint at [path], picking up the songs already in it if
+    /// [resume] is set and starting it over otherwise.
+    pub fn open<P: AsRef<Path>>(path: P, resume: bool) -> Result<Self, String> {
+        let path = path.as_ref();
+
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .map_err(|err| format!("Failed to create checkpoint directory {}: {}", dir.display(), err))?;
+        }
+
+        let entries = match resume {
+            true => read_checkpoint(path)
+                .map_err(|err| format!("Failed to read checkpoint {}: {}", path.display(), err))?,
+            false => HashMap::new()
+        };
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .write(true)
+            .append(resume)
+            .truncate(!resume)
+            .open(path)
+            .map_err(|err| format!("Failed to open checkpoint {}: {}", path.display(), err))?;
+
+        if resume {
+            end_last_line(&mut file)
+                .map_err(|err| format!("Failed to repair checkpoint {}: {}", path.display(), err))?;
+        }
+
+        Ok(CheckpointMiddleware { file, entries })
+    }
+
+    fn record(&mut self, song: &SongDescriptor, lyrics: &str) -> io::Result<()> {
+        let entry = CheckpointEntry { song: song.clone(), lyrics: lyrics.to_string() };
+
+        writeln!(self.file, "{}", serde_json::to_string(&entry)?)?;
+        self.file.flush()
+    }
+}
+
+impl LyricsMiddleware for CheckpointMiddleware {
+    fn before_fetch(&mut self, song: SongDescriptor) -> BeforeFetch {
+        match self.entries.get(&song) {
+            Some(lyrics) => BeforeFetch::Respond(Ok(lyrics.clone())),
+            None => BeforeFetch::Continue(song)
+        }
+    }
+
+    fn after_fetch(&mut self, song: &SongDescriptor, result: LyricsResult) -> LyricsResult {
+        if let Ok(lyrics) = &result {
+            if let Err(err) = self.record(song, lyrics) {
+                eprintln!("Failed to record {} - {} in checkpoint: {}", song.artist, song.name, err);
+            }
+
+            self.entries.insert(song.clone(), lyrics.clone());
+        }
+
+        result
+    }
+}
+
+/// Reads the songs recorded in the checkpoint at [path]; a checkpoint that doesn't
+/// exist is empty, and a line cut short by the run being killed is skipped.
+fn read_checkpoint(path: &Path) -> io::Result<HashMap<SongDescriptor, String>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(HashMap::new()),
+        Err(err) => return Err(err)
+    };
+
+    let mut entries = HashMap::new();
+
+    for line in BufReader::new(file).lines() {
+        if let Ok(entry) = serde_json::from_str::<CheckpointEntry>(&line?) {
+            entries.insert(entry.song, entry.lyrics);
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Ends the last line of [file] if it was cut short so the next entry appended
+/// to it starts on a line of its own.
+fn end_last_line(file: &mut File) -> io::Result<()> {
+    if file.seek(SeekFrom::End(0))? == 0 {
+        return Ok(());
+    }
+
+    let mut last = [0; 1];
+    file.seek(SeekFrom::End(-1))?;
+    file.read_exact(&mut last)?;
+
+    match last[0] {
+        b'\n' => Ok(()),
+        _ => file.write_all(b"\n")
+    }
+}
+
+/// Removes the checkpoint at [path] once the run it was for is done.
+pub fn remove_checkpoint<P: AsRef<Path>>(path: P) -> Result<(), String> {
+    let path = path.as_ref();
+
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound =>
+            Err(format!("Failed to remove checkpoint {}: {}", path.display(), err)),
+        _ => Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+use std::io::Write;
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use liblyrical::analysis::GroupBy;
+use liblyrical::rhyme::Pronouncer;
+
+use super::*;
+use crate::report;
+
+/// The report sections `analyze` writes when none are picked.
+const DEFAULT_SECTIONS: [&str; 5] = ["richness", "sentiment", "rhymes", "languages", "profanity"];
+
+/// The `analyze` subcommand: vocabulary, sentiment, rhyme, language and
+/// explicitness reports for songs.
+pub fn analyze_subcommand<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("analyze")
+        .about("Reports on the vocabulary, sentiment, rhymes, languages and explicitness of songs (all but --distinctive-words by default)")
+        .args(&input_args())
+        .group(input_group())
+        .args(&fetcher_args())
+        .args(&word_count_args())
+        .arg(Arg::with_name("richness")
+            .long("richness")
+            .help("Reports vocabulary richness and repetition metrics per song and for all songs"))
+        .arg(Arg::with_name("distinctive_words")
+            .short("d")
+            .long("distinctive-words")
+            .value_name("N")
+            .takes_value(true)
+            .help("Lists the N words most distinctive to each group of songs (by TF-IDF)"))
+        .arg(Arg::with_name("group_by")
+            .long("group-by")
+            .value_name("KEY")
+            .takes_value(true)
+            .requires("distinctive_words")
+            .help("Sets how songs are grouped for --distinctive-words: artist (default) or primary-artist"))
+        .arg(Arg::with_name("sentiment")
+            .long("sentiment")
+            .help("Reports the sentiment and emotions of each song and each of its lines"))
+        .arg(Arg::with_name("rhymes")
+            .long("rhymes")
+            .help("Reports the rhyme scheme and syllables per line of each song"))
+        .arg(Arg::with_name("pronouncing_dictionary")
+            .long("pronouncing-dictionary")
+            .value_name("FILE")
+            .takes_value(true)
+            .help("Looks up how words sound for --rhymes in the given CMU-format dictionary (e.g. the full cmudict) instead of the small built-in one"))
+        .arg(Arg::with_name("languages")
+            .long("languages")
+            .help("Reports the language each song is detected to be in"))
+        .arg(Arg::with_name("profanity")
+            .long("profanity")
+            .help("Reports how explicit each song is"))
+        .args(&explicit_content_args())
+        .args(&outcome_args())
+        .arg(out_arg())
+}
+
+/// Runs the `analyze` subcommand with the args in [matches].
+pub fn run_analyze(matches: &ArgMatches) -> Result<i32, CommandError> {
+    let options = get_word_count_options(matches)?;
+    let distinctive_words_options = get_distinctive_words_options(matches)?;
+    let profanity_filter = get_profanity_filter(matches)?;
+    let pronouncer = get_pronouncer(matches)?;
+    let sections = get_sections(matches);
+
+    let songs = get_songs_to_fetch(matches)?;
+    let bar = make_progress_bar(matches, &songs);
+    let mut fetcher = make_lyrics_fetcher(matches, &bar)?;
+    let mut out = get_output(matches)?;
+
+    let songs = collect_songs(songs);
+    progress::set_progress_length(&bar, songs.len());
+
+    let lyrics = fetch_lyrics_for_songs(&mut fetcher, &songs);
+    let summary = RunSummary::from_lyrics(&lyrics);
+    let fetched = finish_fetching(matches, &bar);
+
+    let (lyrics, explicit_songs) = match matches.is_present("exclude_explicit") {
+        true => exclude_explicit_songs(lyrics, &profanity_filter),
+        false => (lyrics, vec![])
+    };
+
+    let written = (|| {
+        if matches.is_present("exclude_explicit") {
+            report::write_excluded_songs(&mut out, &explicit_songs)?;
+        }
+
+        if let Some((limit, group_by)) = distinctive_words_options {
+            let word_counts = count_words_for_lyrics(&lyrics, &options);
+
+            report::write_distinctive_words(&mut out, group_by, get_distinctive_words(&word_counts, limit, group_by))?;
+        }
+
+        for section in sections {
+            match section {
+                "richness" => report::write_analysis_for_songs(&mut out, &lyrics)?,
+                "sentiment" => report::write_sentiment_for_songs(&mut out, &lyrics)?,
+                "rhymes" => report::write_rhymes_for_songs(&mut out, &lyrics, &pronouncer)?,
+                "languages" => report::write_languages_for_songs(&mut out, &lyrics)?,
+                "profanity" => report::write_explicitness_for_songs(&mut out, &lyrics, &profanity_filter)?,
+                _ => {}
+            }
+        }
+
+        writeln!(out, "\n\nDone!")
+    })();
+
+    finish_run(matches, &summary, fetched, written.and_then(|_| out.flush()))
+}
+
+/// The report sections picked in [matches], or all of [DEFAULT_SECTIONS] if none
+/// (not even --distinctive-words) were.
+fn get_sections(matches: &ArgMatches) -> Vec<&'static str> {
+    let picked = DEFAULT_SECTIONS.iter()
+        .copied()
+        .filter(|section| matches.is_present(section))
+        .collect::<Vec<_>>();
+
+    match picked.is_empty() && !matches.is_present("distinctive_words") {
+        true => DEFAULT_SECTIONS.to_vec(),
+        false => picked
+    }
+}
+
+/// The [Pronouncer] requested in [matches]: the given dictionary, or the built-in one.
+fn get_pronouncer(matches: &ArgMatches) -> Result<Pronouncer, String> {
+    match matches.value_of("pronouncing_dictionary") {
+        Some(path) => Pronouncer::from_file(path),
+        None => Ok(Pronouncer::new())
+    }
+}
+
+/// Parses the number of distinctive words and the grouping requested in [matches], if any.
+fn get_distinctive_words_options(matches: &ArgMatches) -> Result<Option<(usize, GroupBy)>, String> {
+    let limit = match matches.value_of("distinctive_words") {
+        Some(limit) => limit.parse::<usize>()
+            .map_err(|_| format!("Invalid number of distinctive words \"{}\"", limit))?,
+        None => return Ok(None)
+    };
+
+    let group_by = match matches.value_of("group_by") {
+        Some(group_by) => group_by.parse::<GroupBy>()?,
+        None => GroupBy::Artist
+    };
+
+    Ok(Some((limit, group_by)))
+}
@@ -0,0 +1,134 @@
+use std::io;
+use std::io::Write;
+
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+
+use liblyrical::lyrics::{CacheEntries, CacheEntry, SongKey, CACHE_LOCATION};
+
+use super::*;
+
+/// The `cache` subcommand: inspects and prunes the lyrics cache.
+pub fn cache_subcommand<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("cache")
+        .about("Inspects and prunes the lyrics cache")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .arg(cache_file_arg().global(true))
+        .subcommand(SubCommand::with_name("stats")
+            .about("Counts the cached lyrics, failures and aliases"))
+        .subcommand(SubCommand::with_name("list")
+            .about("Lists the cached songs")
+            .arg(Arg::with_name("failures")
+                .long("failures")
+                .help("Only lists the songs whose lookup failed, and why")))
+        .subcommand(SubCommand::with_name("remove")
+            .about("Removes songs from the cache so they're looked up again")
+            .arg(Arg::with_name("songs")
+                .value_name("\"ARTIST - TITLE\"")
+                .required(true)
+                .multiple(true)))
+        .subcommand(SubCommand::with_name("clear-failures")
+            .about("Removes the songs whose lookup failed so they're looked up again"))
+        .subcommand(SubCommand::with_name("clear")
+            .about("Removes every song from the cache"))
+}
+
+/// Runs the `cache` subcommand with the args in [matches].
+pub fn run_cache(matches: &ArgMatches) -> Result<(), CommandError> {
+    let (command, command_matches) = match matches.subcommand() {
+        (command, Some(command_matches)) => (command, command_matches),
+        (command, None) => return Err(CommandError::Usage(format!("Unknown cache command \"{}\"", command)))
+    };
+
+    let path = get_cache_path(matches);
+    let mut cache = CacheEntries::from_file(path).map_err(CommandError::Runtime)?;
+    let mut out = io::stdout();
+
+    let written = match command {
+        "stats" => write_cache_stats(&mut out, &cache),
+        "list" => write_cache_entries(&mut out, &cache, command_matches.is_present("failures")),
+        "remove" => {
+            let songs = command_matches.values_of("songs")
+                .into_iter()
+                .flatten()
+                .map(|song| input::split_artist_and_title(song)
+                    .ok_or_else(|| format!("Invalid song \"{}\"; expected \"Artist - Title\"", song)))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let removed = songs.iter()
+                .filter(|song| cache.remove(song).is_some())
+                .count();
+
+            cache.to_file(path).map_err(CommandError::Runtime)?;
+            writeln!(out, "Removed {} of {} songs", removed, songs.len())
+        },
+        "clear-failures" => {
+            let before = cache.len();
+            cache.retain(|_, entry| !matches!(entry, CacheEntry::Failure(_)));
+
+            cache.to_file(path).map_err(CommandError::Runtime)?;
+            writeln!(out, "Removed {} failures", before - cache.len())
+        },
+        "clear" => {
+            let removed = cache.len();
+
+            CacheEntries::new().to_file(path).map_err(CommandError::Runtime)?;
+            writeln!(out, "Removed {} songs", removed)
+        },
+        command => return Err(CommandError::Usage(format!("Unknown cache command \"{}\"", command)))
+    };
+
+    written.map_err(|err| CommandError::Runtime(format!("Failed to write to stdout: {}", err)))
+}
+
+/// The cache file picked in [matches] (the `cache` subcommand's), which can be
+/// passed before or after the cache command.
+pub fn get_cache_path<'a>(matches: &'a ArgMatches) -> &'a str {
+    matches.subcommand()
+        .1
+        .and_then(|command_matches| command_matches.value_of("cache_file"))
+        .or_else(|| matches.value_of("cache_file"))
+        .unwrap_or(CACHE_LOCATION)
+}
+
+/// Writes how many lyrics, failures and aliases [cache] holds.
+pub fn write_cache_stats(out: &mut dyn Write, cache: &CacheEntries) -> io::Result<()> {
+    let (lyrics, failures, aliases) = cache.iter()
+        .fold((0, 0, 0), |(lyrics, failures, aliases), (_, entry)| match entry {
+            CacheEntry::Success(_) => (lyrics + 1, failures, aliases),
+            CacheEntry::Failure(_) => (lyrics, failures + 1, aliases),
+            CacheEntry::Alias(_) => (lyrics, failures, aliases + 1)
+        });
+
+    writeln!(out, "Songs: {}", cache.len())?;
+    writeln!(out, "Lyrics: {}", lyrics)?;
+    writeln!(out, "Failures: {}", failures)?;
+    writeln!(out, "Aliases: {}", aliases)
+}
+
+/// Writes each song in [cache] (only the failed ones if [failures_only] is set)
+/// and what's cached for it, sorted by artist and then name.
+pub fn write_cache_entries(out: &mut dyn Write, cache: &CacheEntries, failures_only: bool) -> io::Result<()> {
+    let mut entries = cache.iter()
+        .filter(|(_, entry)| !failures_only || matches!(entry, CacheEntry::Failure(_)))
+        .collect::<Vec<_>>();
+
+    entries.sort_by_key(|&(key, _)| key);
+
+    for (key, entry) in entries {
+        match entry {
+            CacheEntry::Success(_) => writeln!(out, "{}: lyrics", describe_key(key))?,
+            CacheEntry::Failure(reason) => writeln!(out, "{}: failed ({})", describe_key(key), reason)?,
+            CacheEntry::Alias(song) => writeln!(out, "{}: alias of {} - {}", describe_key(key), song.artist, song.name)?
+        }
+    }
+
+    Ok(())
+}
+
+/// "artist - name" for [key], or just its uri for songs only known by their uri.
+fn describe_key(key: &SongKey) -> String {
+    match key.artist.is_empty() {
+        true => key.name.clone(),
+        false => format!("{} - {}", key.artist, key.name)
+    }
+}
@@ -0,0 +1,147 @@
+use std::io::Write;
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use liblyrical::analysis;
+use liblyrical::analysis::GroupBy;
+use liblyrical::lyrics::SongDescriptor;
+use liblyrical::word_count;
+use liblyrical::word_count::WordCounts;
+
+use super::*;
+use crate::report;
+
+/// How many words and song pairs `compare` lists by default.
+const DEFAULT_TOP: usize = 10;
+
+/// A set of songs to compare and the name it's reported under.
+type SongSet = (String, Vec<SongDescriptor>);
+
+/// The `compare` subcommand: how the vocabularies of two sets of songs differ.
+pub fn compare_subcommand<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("compare")
+        .about("Compares the vocabularies of two sets of songs: two inputs (--left and --right) or two artists in one input (--artists)")
+        .args(&input_args())
+        .group(input_group())
+        .arg(Arg::with_name("left")
+            .long("left")
+            .value_name("FILE")
+            .takes_value(true)
+            .required_unless("artists")
+            .requires("right")
+            .conflicts_with_all(&["artists", "json_file", "json"])
+            .help("Sets the file with the first set of songs, in the --input-format"))
+        .arg(Arg::with_name("right")
+            .long("right")
+            .value_name("FILE")
+            .takes_value(true)
+            .requires("left")
+            .help("Sets the file with the second set of songs, in the --input-format"))
+        .arg(Arg::with_name("artists")
+            .long("artists")
+            .value_names(&["LEFT", "RIGHT"])
+            .number_of_values(2)
+            .help("Compares the songs of two (primary) artists in the input"))
+        .arg(Arg::with_name("top")
+            .short("n")
+            .long("top")
+            .value_name("N")
+            .takes_value(true)
+            .help("Sets how many words of each kind and song pairs to list (10 by default)"))
+        .args(&fetcher_args())
+        .args(&word_count_args())
+        .args(&outcome_args())
+        .arg(out_arg())
+}
+
+/// Runs the `compare` subcommand with the args in [matches].
+pub fn run_compare(matches: &ArgMatches) -> Result<i32, CommandError> {
+    let options = get_word_count_options(matches)?;
+    let limit = get_top(matches)?;
+    let ((left_name, left), (right_name, right)) = get_song_sets(matches)?;
+
+    let bar = progress::make_progress_bar(!matches.is_present("no_progress"));
+    let mut fetcher = make_lyrics_fetcher(matches, &bar)?;
+    let mut out = get_output(matches)?;
+
+    let songs = left.into_iter().chain(right.iter().cloned()).collect::<Vec<_>>();
+    progress::set_progress_length(&bar, songs.len());
+
+    let lyrics = fetch_lyrics_for_songs(&mut fetcher, &songs);
+    let summary = RunSummary::from_lyrics(&lyrics);
+    let fetched = finish_fetching(matches, &bar);
+
+    let word_counts = count_words_for_lyrics(&lyrics, &options);
+    let (left_counts, right_counts) = word_counts.split_at(songs.len() - right.len());
+    let (left_counts, right_counts) = (successful_word_counts(left_counts), successful_word_counts(right_counts));
+
+    let comparison = analysis::compare_word_counts(
+        &aggregate_word_counts(&left_counts),
+        &aggregate_word_counts(&right_counts),
+        limit);
+    let pairs = analysis::most_similar_songs(&left_counts, &right_counts, limit);
+
+    let written = report::write_comparison(&mut out, (&left_name, &right_name), &comparison, &pairs)
+        .and_then(|_| writeln!(out, "\n\nDone!"));
+
+    finish_run(matches, &summary, fetched, written.and_then(|_| out.flush()))
+}
+
+/// The two named sets of songs to compare requested in [matches]: the songs in the
+/// --left and --right files, or the songs of each of the --artists in the input.
+fn get_song_sets(matches: &ArgMatches) -> Result<(SongSet, SongSet), String> {
+    let format = get_input_format(matches)?;
+
+    let (left, right) = match matches.values_of("artists") {
+        Some(mut artists) => {
+            let (left, right) = (artists.next().unwrap(), artists.next().unwrap());
+            let songs = collect_songs(get_songs_to_fetch(matches)?);
+
+            ((left.to_string(), songs_by_artist(&songs, left)), (right.to_string(), songs_by_artist(&songs, right)))
+        },
+        None => {
+            let (left, right) = (matches.value_of("left").unwrap(), matches.value_of("right").unwrap());
+
+            ((left.to_string(), collect_songs(read_songs_file(left, format)?)), (right.to_string(), collect_songs(read_songs_file(right, format)?)))
+        }
+    };
+
+    for (name, songs) in [&left, &right] {
+        if songs.is_empty() {
+            return Err(format!("No songs to compare for \"{}\"", name));
+        }
+    }
+
+    Ok((left, right))
+}
+
+/// The [songs] whose primary artist is [artist], ignoring case.
+pub fn songs_by_artist(songs: &[SongDescriptor], artist: &str) -> Vec<SongDescriptor> {
+    let artist = artist.trim().to_lowercase();
+
+    songs.iter()
+        .filter(|song| GroupBy::PrimaryArtist.key(song).to_lowercase() == artist)
+        .cloned()
+        .collect()
+}
+
+/// Parses the number of words and song pairs to list requested in [matches].
+fn get_top(matches: &ArgMatches) -> Result<usize, String> {
+    match matches.value_of("top") {
+        Some(top) => top.parse::<usize>()
+            .map_err(|_| format!("Invalid number of words to list \"{}\"", top)),
+        None => Ok(DEFAULT_TOP)
+    }
+}
+
+/// The [WordCounts] of each song in [word_counts] that was counted.
+fn successful_word_counts<'a>(word_counts: &[SongWordCountsResult<'a>]) -> Vec<(&'a SongDescriptor, WordCounts)> {
+    word_counts.iter()
+        .filter_map(|(song, counts)| counts.as_ref().ok().map(|counts| (*song, counts.clone())))
+        .collect()
+}
+
+/// Aggregates the [WordCounts] of every song in [word_counts].
+fn aggregate_word_counts(word_counts: &[(&SongDescriptor, WordCounts)]) -> WordCounts {
+    word_count::aggregate_word_counts(word_counts.iter().map(|(_, counts)| counts.clone()).collect())
+}
@@ -0,0 +1,275 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+use indicatif::ProgressBar;
+
+use liblyrical::lyrics::{FetchError, LyricsFetcher, SongDescriptor};
+use liblyrical::word_count::{Collation, SortOrder, WordCountFilter, WordCountFilterBuilder};
+
+use super::*;
+use crate::report;
+use crate::report::{OutputFormat, Report};
+
+/// Reads lyrics from local text files instead of fetching them, so songs that
+/// aren't online can be counted too.
+#[derive(Debug, Default)]
+pub struct TextFileLyricsFetcher {
+    files: Vec<(SongDescriptor, PathBuf)>,
+}
+
+impl TextFileLyricsFetcher {
+    /// Makes a fetcher for the files at [paths], each of which is the song its
+    /// name says ("Artist - Title.txt") or, failing that, a song named after it.
+    pub fn new<P: AsRef<Path>>(paths: impl IntoIterator<Item = P>) -> Self {
+        let files = paths.into_iter()
+            .map(|path| {
+                let path = path.as_ref();
+                let stem = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+
+                let song = input::split_artist_and_title(&stem)
+                    .unwrap_or(SongDescriptor { name: stem, artist: String::new(), uri: None });
+
+                (song, path.to_path_buf())
+            })
+            .collect();
+
+        TextFileLyricsFetcher { files }
+    }
+
+    /// The songs the files are for, in the order they were given.
+    pub fn songs(&self) -> Songs {
+        let songs = self.files.iter()
+            .map(|(song, _)| Ok(song.clone()))
+            .collect::<Vec<_>>();
+
+        Box::new(songs.into_iter())
+    }
+}
+
+impl LyricsFetcher for TextFileLyricsFetcher {
+    fn fetch_lyrics(&mut self, song: &SongDescriptor) -> Result<String, FetchError> {
+        let (_, path) = self.files.iter()
+            .find(|(file_song, _)| file_song == song)
+            .ok_or_else(|| FetchError::not_found(format!("No text file for {} - {}", song.artist, song.name)))?;
+
+        fs::read_to_string(path)
+            .map_err(|err| FetchError::from(format!("Failed to read {}: {}", path.display(), err)))
+    }
+}
+
+/// The `count` subcommand: word (and phrase) frequencies for songs or local text files.
+pub fn count_subcommand<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("count")
+        .about("Counts the words used in songs, fetching their lyrics or reading them from text files")
+        .args(&input_args())
+        .group(input_group())
+        .arg(Arg::with_name("text_file")
+            .long("text-file")
+            .value_name("FILE")
+            .takes_value(true)
+            .multiple(true)
+            .conflicts_with_all(&["json_file", "json", "input_format"])
+            .help("Counts the lyrics in the given text files (named \"Artist - Title.txt\") instead of fetching them"))
+        .args(&fetcher_args())
+        .args(&word_count_args())
+        .arg(Arg::with_name("top")
+            .short("t")
+            .long("top")
+            .value_name("N")
+            .takes_value(true)
+            .help("Only lists the N most common words (or first N in --sort order)"))
+        .arg(Arg::with_name("min_count")
+            .long("min-count")
+            .value_name("COUNT")
+            .takes_value(true)
+            .help("Only lists words used at least COUNT times"))
+        .arg(Arg::with_name("include")
+            .long("include")
+            .value_name("REGEX")
+            .takes_value(true)
+            .help("Only lists words matching the given regular expression"))
+        .arg(Arg::with_name("exclude")
+            .long("exclude")
+            .value_name("REGEX")
+            .takes_value(true)
+            .help("Leaves out words matching the given regular expression"))
+        .arg(Arg::with_name("sort")
+            .long("sort")
+            .value_name("ORDER")
+            .takes_value(true)
+            .possible_values(&["asc", "desc", "alpha"])
+            .help("Sets how words are listed: desc (most common first, default), asc or alpha"))
+        .arg(Arg::with_name("collation")
+            .long("collation")
+            .value_name("COLLATION")
+            .takes_value(true)
+            .possible_values(&["codepoint", "natural"])
+            .help("Sets how words are compared when sorting by word or breaking ties: codepoint (default) or natural (ignoring case and accents)"))
+        .arg(Arg::with_name("per_song")
+            .long("per-song")
+            .help("Also lists the words of each song separately (text output only)"))
+        .arg(Arg::with_name("ngrams")
+            .short("n")
+            .long("ngrams")
+            .value_name("SIZES")
+            .takes_value(true)
+            .help("Also lists the most repeated phrases of the given comma-separated word counts (e.g. 2,3; text output only)"))
+        .args(&explicit_content_args())
+        .arg(Arg::with_name("mask_profanity")
+            .long("mask-profanity")
+            .help("Masks profane words in word and phrase counts (e.g. \"s***\")"))
+        .arg(Arg::with_name("output")
+            .short("o")
+            .long("output")
+            .value_name("FORMAT")
+            .takes_value(true)
+            .possible_values(&["text", "json", "ndjson", "csv", "tsv", "markdown"])
+            .help("Sets the report format: text (default), or json, csv, tsv or markdown for per-song and aggregated word counts, failures and run stats only; ndjson writes each song's word counts as soon as they're done"))
+        .args(&outcome_args())
+        .arg(out_arg())
+}
+
+/// Runs the `count` subcommand with the args in [matches].
+pub fn run_count(matches: &ArgMatches) -> Result<i32, CommandError> {
+    let options = get_word_count_options(matches)?;
+    let filter = get_word_count_filter(matches)?;
+    let ngram_sizes = get_ngram_sizes(matches)?;
+    let profanity_filter = get_profanity_filter(matches)?;
+    let output_format = get_output_format(matches)?;
+
+    // The other formats always list each song's word counts and have no room for phrases.
+    if output_format != OutputFormat::Text {
+        for (arg, flag) in &[("ngrams", "--ngrams"), ("per_song", "--per-song")] {
+            if matches.is_present(arg) {
+                return Err(CommandError::Usage(format!("{} only applies to --output text", flag)));
+            }
+        }
+    }
+
+    // Text files are read too quickly to need progress or checkpoints.
+    let (songs, mut fetcher, bar): (Songs, Box<dyn LyricsFetcher>, Option<ProgressBar>) = match matches.values_of("text_file") {
+        Some(paths) => {
+            let fetcher = TextFileLyricsFetcher::new(paths);
+
+            (fetcher.songs(), Box::new(fetcher), None)
+        },
+        None => {
+            let songs = get_songs_to_fetch(matches)?;
+            let bar = make_progress_bar(matches, &songs);
+            let fetcher = make_lyrics_fetcher(matches, &bar)?;
+
+            (songs, Box::new(fetcher), Some(bar))
+        }
+    };
+
+    let mut out = get_output(matches)?;
+
+    if output_format == OutputFormat::Ndjson {
+        let exclude_explicit = Some(&profanity_filter).filter(|_| matches.is_present("exclude_explicit"));
+        let mask = Some(&profanity_filter).filter(|_| matches.is_present("mask_profanity"));
+
+        let (summary, written) = stream_word_counts(&mut out, &mut fetcher, songs, &options, &filter, exclude_explicit, mask);
+
+        let fetched = bar.as_ref().map_or(Ok(()), |bar| finish_fetching(matches, bar));
+
+        return finish_run(matches, &summary, fetched, written);
+    }
+
+    let songs = collect_songs(songs);
+
+    if let Some(bar) = &bar {
+        progress::set_progress_length(bar, songs.len());
+    }
+
+    let lyrics = fetch_lyrics_for_songs(&mut fetcher, &songs);
+    let summary = RunSummary::from_lyrics(&lyrics);
+
+    let fetched = bar.as_ref().map_or(Ok(()), |bar| finish_fetching(matches, bar));
+
+    let (lyrics, explicit_songs) = match matches.is_present("exclude_explicit") {
+        true => exclude_explicit_songs(lyrics, &profanity_filter),
+        false => (lyrics, vec![])
+    };
+
+    let mut word_counts = count_words_for_lyrics(&lyrics, &options);
+
+    let mut phrase_counts = match ngram_sizes.is_empty() {
+        true => None,
+        false => Some(count_phrases_for_lyrics(&lyrics, &ngram_sizes, &options))
+    };
+
+    if matches.is_present("mask_profanity") {
+        word_counts = mask_profanity(word_counts, &profanity_filter);
+        phrase_counts = phrase_counts.map(|phrase_counts| mask_profanity(phrase_counts, &profanity_filter));
+    }
+
+    let written = match output_format {
+        OutputFormat::Text => (|| {
+            if matches.is_present("exclude_explicit") {
+                report::write_excluded_songs(&mut out, &explicit_songs)?;
+            }
+
+            report::write_word_counts_for_songs(&mut out, word_counts, phrase_counts, &filter, matches.is_present("per_song"))?;
+
+            writeln!(out, "\n\nDone!")
+        })(),
+        format => report::write_report(&mut out, &Report::new(&word_counts, &explicit_songs, &filter), format)
+    };
+
+    finish_run(matches, &summary, fetched, written.and_then(|_| out.flush()))
+}
+
+/// Builds the [WordCountFilter] requested in [matches].
+fn get_word_count_filter(matches: &ArgMatches) -> Result<WordCountFilter, String> {
+    let top = match matches.value_of("top") {
+        Some(top) => Some(top.parse::<usize>().map_err(|_| format!("Invalid number of words \"{}\"", top))?),
+        None => None
+    };
+
+    let min_count = match matches.value_of("min_count") {
+        Some(min_count) => Some(min_count.parse::<i32>().map_err(|_| format!("Invalid minimum count \"{}\"", min_count))?),
+        None => None
+    };
+
+    let sort_order = match matches.value_of("sort") {
+        Some(sort_order) => sort_order.parse::<SortOrder>()?,
+        None => SortOrder::Descending
+    };
+
+    let collation = match matches.value_of("collation") {
+        Some(collation) => collation.parse::<Collation>()?,
+        None => Collation::CodePoint
+    };
+
+    WordCountFilterBuilder::default()
+        .sort_order(sort_order)
+        .collation(collation)
+        .top(top)
+        .min_count(min_count)
+        .include(get_regex(matches, "include")?)
+        .exclude(get_regex(matches, "exclude")?)
+        .build()
+}
+
+/// Parses the comma-separated phrase lengths requested in [matches], if any.
+fn get_ngram_sizes(matches: &ArgMatches) -> Result<Vec<usize>, String> {
+    match matches.value_of("ngrams") {
+        Some(sizes) => sizes.split(',')
+            .map(|size| match size.trim().parse::<usize>() {
+                Ok(size) if size > 0 => Ok(size),
+                _ => Err(format!("Invalid n-gram size \"{}\"; expected a positive number", size))
+            })
+            .collect(),
+        None => Ok(vec![])
+    }
+}
+
+/// Parses the [OutputFormat] requested in [matches].
+fn get_output_format(matches: &ArgMatches) -> Result<OutputFormat, String> {
+    match matches.value_of("output") {
+        Some(format) => format.parse::<OutputFormat>(),
+        None => Ok(OutputFormat::Text)
+    }
+}
@@ -0,0 +1,109 @@
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use liblyrical::lyrics::{FailureCategory, LyricsFetcher, SongDescriptor};
+
+use super::*;
+use crate::interrupt;
+
+/// Characters that can't be used in file names on at least one platform.
+const RESERVED_FILE_NAME_CHARS: [char; 9] = ['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+/// The `fetch` subcommand: prints or saves the lyrics of songs.
+pub fn fetch_subcommand<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("fetch")
+        .about("Fetches the lyrics of songs and prints them or saves them as text files")
+        .args(&input_args())
+        .group(input_group())
+        .args(&fetcher_args())
+        .arg(Arg::with_name("save_dir")
+            .long("save-dir")
+            .value_name("DIR")
+            .takes_value(true)
+            .help("Saves each song's lyrics to \"Artist - Title.txt\" in the given directory instead of printing them"))
+        .args(&outcome_args())
+        .arg(out_arg())
+}
+
+/// Runs the `fetch` subcommand with the args in [matches].
+pub fn run_fetch(matches: &ArgMatches) -> Result<i32, CommandError> {
+    let songs = get_songs_to_fetch(matches)?;
+    let save_dir = matches.value_of("save_dir").map(Path::new);
+
+    if let Some(save_dir) = save_dir {
+        fs::create_dir_all(save_dir)
+            .map_err(|err| CommandError::Runtime(format!("Failed to create {}: {}", save_dir.display(), err)))?;
+    }
+
+    let bar = make_progress_bar(matches, &songs);
+    let mut fetcher = make_lyrics_fetcher(matches, &bar)?;
+    let mut out = get_output(matches)?;
+
+    let mut summary = RunSummary::default();
+    let written = fetch_songs(&mut out, &mut fetcher, songs, save_dir, &mut summary)
+        .and_then(|_| out.flush());
+
+    let fetched = finish_fetching(matches, &bar);
+    finish_run(matches, &summary, fetched, written)
+}
+
+/// Fetches the lyrics of each of [songs] as they're read, writing them to [out] or,
+/// if [save_dir] is set, saving them to a file there and writing its path to [out].
+/// Each song is recorded in [summary], including the ones skipped once lyrical is
+/// interrupted.
+pub fn fetch_songs(
+    out: &mut dyn Write,
+    fetcher: &mut dyn LyricsFetcher,
+    songs: Songs,
+    save_dir: Option<&Path>,
+    summary: &mut RunSummary) -> io::Result<()> {
+    for song in songs {
+        let song = match song {
+            Ok(song) => song,
+            Err(err) => {
+                eprintln!("Skipping song: {}", err);
+                continue;
+            }
+        };
+
+        let lyrics = interrupt::fetch_lyrics(fetcher, &song);
+        summary.record(&song, &lyrics);
+
+        let lyrics = match lyrics {
+            Ok(lyrics) => lyrics,
+            Err(err) if err.category == FailureCategory::Skipped => continue,
+            Err(err) => {
+                eprintln!("Failed to fetch lyrics for {} - {}: {}", song.artist, song.name, err);
+                continue;
+            }
+        };
+
+        match save_dir {
+            Some(save_dir) => {
+                let path = save_dir.join(lyrics_file_name(&song));
+
+                fs::write(&path, lyrics)?;
+                writeln!(out, "{}", path.display())?;
+            },
+            None => writeln!(out, "{} - {}\n\n{}\n", song.artist, song.name, lyrics.trim_end())?
+        }
+    }
+
+    Ok(())
+}
+
+/// The name of the file [song]'s lyrics are saved to: "Artist - Title.txt", with
+/// characters that can't be in a file name replaced.
+pub fn lyrics_file_name(song: &SongDescriptor) -> String {
+    format!("{} - {}.txt", song.artist, song.name)
+        .chars()
+        .map(|c| match RESERVED_FILE_NAME_CHARS.contains(&c) || c.is_control() {
+            true => '_',
+            false => c
+        })
+        .collect()
+}
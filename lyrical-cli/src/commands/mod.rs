@@ -0,0 +1,449 @@
+mod analyze;
+mod cache;
+mod compare;
+mod count;
+mod fetch;
+mod serve;
+mod shared;
+
+pub use analyze::*;
+pub use cache::*;
+pub use compare::*;
+pub use count::*;
+pub use fetch::*;
+pub use serve::*;
+pub use shared::*;
+
+use std::fs::File;
+use std::io;
+use std::io::{BufReader, BufWriter, Cursor, Write};
+use std::path::Path;
+
+use clap::{App, Arg, ArgGroup, ArgMatches};
+use indicatif::ProgressBar;
+use regex::Regex;
+
+use liblyrical::analysis::ProfanityFilter;
+use liblyrical::lyrics;
+use liblyrical::lyrics::{LoggingMiddleware, LyricsFetcher, LyricsFetcherConfig, LyricsFetcherConfigBuilder, LyricsMiddleware};
+use liblyrical::word_count::{Language, StopWords, WordCountOptions, WordCountOptionsBuilder};
+
+use crate::checkpoint;
+use crate::checkpoint::{CheckpointMiddleware, CHECKPOINT_LOCATION};
+use crate::input;
+use crate::input::{InputFormat, Songs};
+use crate::interrupt;
+use crate::outcome::{CommandError, RunSummary, EXIT_ERROR, EXIT_INTERRUPTED, EXIT_SUCCESS};
+use crate::progress;
+use crate::progress::ProgressMiddleware;
+use crate::report;
+use crate::report::FailureReport;
+
+/// The subcommands lyrical can be run with.
+pub fn subcommands<'a, 'b>() -> Vec<App<'a, 'b>> {
+    vec![
+        fetch_subcommand(),
+        count_subcommand(),
+        analyze_subcommand(),
+        compare_subcommand(),
+        cache_subcommand(),
+        serve_subcommand(),
+    ]
+}
+
+/// Runs the subcommand picked in [matches], returning the code lyrical should exit with.
+pub fn run(matches: &ArgMatches) -> Result<i32, CommandError> {
+    match matches.subcommand() {
+        ("fetch", Some(matches)) => run_fetch(matches),
+        ("count", Some(matches)) => run_count(matches),
+        ("analyze", Some(matches)) => run_analyze(matches),
+        ("compare", Some(matches)) => run_compare(matches),
+        ("cache", Some(matches)) => run_cache(matches).map(|_| EXIT_SUCCESS),
+        ("serve", Some(matches)) => run_serve(matches),
+        (name, _) => Err(CommandError::Usage(format!("Unknown command \"{}\"", name)))
+    }
+}
+
+/// The args that pick the songs a subcommand works on.
+fn input_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("json_file")
+            .short("f")
+            .long("json-file")
+            .value_name("JSON_FILE")
+            .takes_value(true)
+            .help("Sets the file to use as input, or - for stdin (the default)"),
+        Arg::with_name("json")
+            .short("j")
+            .long("json")
+            .value_name("JSON")
+            .help("Sets the json to use as input"),
+        Arg::with_name("input_format")
+            .short("i")
+            .long("input-format")
+            .value_name("FORMAT")
+            .takes_value(true)
+            .possible_values(&["json", "ndjson", "text", "spotify", "exportify", "itunes", "m3u", "m3u8", "pls", "xspf"])
+            .help("Sets the format of the input: json (default), ndjson (a json song per line), text (an \"Artist - Title\" per line), spotify (playlist export JSON), exportify (CSV), itunes (library XML), or an m3u/m3u8, pls or xspf playlist"),
+    ]
+}
+
+fn input_group() -> ArgGroup<'static> {
+    ArgGroup::with_name("json_source")
+        .args(&["json_file", "json"])
+}
+
+/// The arg that sets the cache file, shared by every subcommand that touches the cache.
+fn cache_file_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("cache_file")
+        .long("cache-file")
+        .value_name("CACHE_FILE")
+        .takes_value(true)
+        .help("Sets the file lyrics are cached in (default ./cache/lyrics.json)")
+}
+
+/// The args that make up the [LyricsFetcherConfig] (see [get_fetcher_config]).
+fn fetcher_config_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        cache_file_arg(),
+        Arg::with_name("proxy")
+            .long("proxy")
+            .value_name("URL")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .help("Fetches lyrics through the given proxy, or \"direct\" for no proxy; can be repeated to fail over between several (defaults to a built-in list)"),
+        Arg::with_name("no_retry_failures")
+            .long("no-retry-failures")
+            .help("Fails songs whose lookup failed on an earlier run instead of looking them up again"),
+    ]
+}
+
+/// The args that set up the [LyricsFetcher] the fetching subcommands share.
+fn fetcher_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    let mut args = fetcher_config_args();
+
+    args.extend(vec![
+        Arg::with_name("checkpoint")
+            .long("checkpoint")
+            .value_name("FILE")
+            .takes_value(true)
+            .min_values(0)
+            .help("Records each song's lyrics in the given file (default ./cache/checkpoint.ndjson) as they're fetched, so the run can be resumed if it's interrupted; the file is removed once the run finishes"),
+        Arg::with_name("resume")
+            .long("resume")
+            .help("Picks up where an interrupted run left off, reusing the songs recorded in its --checkpoint (and checkpointing this run too)"),
+        Arg::with_name("no_progress")
+            .long("no-progress")
+            .help("Logs each fetch instead of showing a progress bar"),
+    ]);
+
+    args
+}
+
+/// The args that decide how a run that fetches songs is judged.
+fn outcome_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("fail_under")
+            .long("fail-under")
+            .value_name("PCT")
+            .takes_value(true)
+            .validator(validate_percentage)
+            .help("Only exits with the partial failure code (3) if fewer than PCT percent of songs were fetched, rather than if any failed; a run where every song failed still exits with 4"),
+        Arg::with_name("failure_report")
+            .long("failure-report")
+            .value_name("FILE")
+            .takes_value(true)
+            .help("Writes each song that couldn't be fetched, with the category of the error, to the given file as JSON"),
+    ]
+}
+
+fn validate_percentage(percentage: String) -> Result<(), String> {
+    match percentage.trim().parse::<f64>() {
+        Ok(percentage) if (0f64..=100f64).contains(&percentage) => Ok(()),
+        _ => Err(format!("Invalid percentage \"{}\"; expected a number from 0 to 100", percentage))
+    }
+}
+
+/// The args that control how words are counted.
+fn word_count_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("stop_words")
+            .short("s")
+            .long("stop-words")
+            .value_name("LANGUAGES")
+            .takes_value(true)
+            .help("Ignores common words in the given comma-separated languages (e.g. en,es), or \"auto\" for each song's detected language"),
+        Arg::with_name("stop_words_file")
+            .long("stop-words-file")
+            .value_name("STOP_WORDS_FILE")
+            .takes_value(true)
+            .help("Ignores the words listed (one per line) in the given file"),
+        Arg::with_name("expand_contractions")
+            .long("expand-contractions")
+            .help("Counts contractions like \"i'm\" and \"wanna\" as the words they stand for"),
+        Arg::with_name("lemmatize")
+            .long("lemmatize")
+            .help("Counts irregular word forms (e.g. \"went\") as their lemma (e.g. \"go\")"),
+        Arg::with_name("stem")
+            .long("stem")
+            .value_name("LANGUAGE")
+            .takes_value(true)
+            .help("Groups word forms (e.g. \"dance\", \"dancing\") using the stemmer for the given language, or \"auto\" for each song's detected language"),
+    ]
+}
+
+/// The args that deal with explicit songs.
+fn explicit_content_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("exclude_explicit")
+            .long("exclude-explicit")
+            .help("Leaves explicit songs out of all counts and reports"),
+        Arg::with_name("profanity_file")
+            .long("profanity-file")
+            .value_name("PROFANITY_FILE")
+            .takes_value(true)
+            .help("Also treats the words listed (one per line) in the given file as profane"),
+    ]
+}
+
+/// The arg that sends a subcommand's output to a file.
+fn out_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("out")
+        .long("out")
+        .value_name("FILE")
+        .takes_value(true)
+        .help("Writes the output to the given file instead of stdout")
+}
+
+/// Opens the songs to fetch from the input provided in [matches]: inline json, a
+/// file or stdin.
+fn get_songs_to_fetch(matches: &ArgMatches) -> Result<Songs, String> {
+    let format = get_input_format(matches)?;
+
+    match (matches.value_of("json"), matches.value_of("json_file")) {
+        (Some(json), _) => input::read_songs(Cursor::new(json.to_string()), format, Path::new(".")),
+        (None, Some(path)) if path != "-" => read_songs_file(path, format),
+        _ => input::read_songs(BufReader::new(io::stdin()), format, Path::new("."))
+    }
+}
+
+/// The input format requested in [matches], json by default.
+fn get_input_format(matches: &ArgMatches) -> Result<InputFormat, String> {
+    match matches.value_of("input_format") {
+        Some(format) => format.parse::<InputFormat>(),
+        None => Ok(InputFormat::Json)
+    }
+}
+
+/// Opens the songs in the file at [path], which is in [format].
+fn read_songs_file(path: &str, format: InputFormat) -> Result<Songs, String> {
+    let file = File::open(path)
+        .map_err(|err| format!("Failed to read {}: {}", path, err))?;
+
+    // Playlists list files relative to where they are.
+    let base_dir = Path::new(path).parent().unwrap_or_else(|| Path::new("."));
+
+    input::read_songs(BufReader::new(file), format, base_dir)
+}
+
+/// Builds the [StopWords] requested in [matches], if any.
+fn get_stop_words(matches: &ArgMatches) -> Result<Option<StopWords>, String> {
+    let languages = match matches.value_of("stop_words") {
+        Some(languages) => languages.split(',')
+            .filter(|language| !is_auto(language))
+            .map(|language| language.parse::<Language>())
+            .collect::<Result<Vec<_>, _>>()?,
+        None => vec![]
+    };
+
+    let mut stop_words = StopWords::for_languages(&languages);
+
+    if let Some(path) = matches.value_of("stop_words_file") {
+        stop_words.extend(StopWords::from_file(path)?);
+    }
+
+    match stop_words.is_empty() {
+        true => Ok(None),
+        false => Ok(Some(stop_words))
+    }
+}
+
+/// Builds the [WordCountOptions] requested in [matches].
+fn get_word_count_options(matches: &ArgMatches) -> Result<WordCountOptions, String> {
+    let stemming = match matches.value_of("stem") {
+        Some(language) if !is_auto(language) => Some(language.parse::<Language>()?),
+        _ => None
+    };
+
+    let language_stop_words = matches.value_of("stop_words")
+        .map(|languages| languages.split(',').any(is_auto))
+        .unwrap_or(false);
+
+    WordCountOptionsBuilder::default()
+        .stop_words(get_stop_words(matches)?)
+        .expand_contractions(matches.is_present("expand_contractions"))
+        .lemmatize(matches.is_present("lemmatize"))
+        .stemming(stemming)
+        .language_stop_words(language_stop_words)
+        .language_stemming(matches.value_of("stem").map(is_auto).unwrap_or(false))
+        .build()
+}
+
+/// Compiles the regular expression passed as [arg] in [matches], if any.
+fn get_regex(matches: &ArgMatches, arg: &str) -> Result<Option<Regex>, String> {
+    matches.value_of(arg)
+        .map(|pattern| Regex::new(pattern).map_err(|err| format!("Invalid --{} pattern \"{}\": {}", arg, pattern, err)))
+        .transpose()
+}
+
+/// Whether [language] asks for each song's detected language to be used.
+fn is_auto(language: &str) -> bool {
+    language.trim().eq_ignore_ascii_case("auto")
+}
+
+/// Builds the [ProfanityFilter] requested in [matches].
+fn get_profanity_filter(matches: &ArgMatches) -> Result<ProfanityFilter, String> {
+    let mut profanity_filter = ProfanityFilter::new();
+
+    if let Some(path) = matches.value_of("profanity_file") {
+        profanity_filter.extend(ProfanityFilter::from_file(path)?);
+    }
+
+    Ok(profanity_filter)
+}
+
+/// Builds the [LyricsFetcherConfig] requested in [matches].
+pub fn get_fetcher_config(matches: &ArgMatches) -> Result<LyricsFetcherConfig, String> {
+    let mut config = LyricsFetcherConfigBuilder::default();
+
+    if let Some(path) = matches.value_of("cache_file") {
+        config.cache_path(path);
+    }
+
+    if let Some(proxies) = matches.values_of("proxy") {
+        config.proxies(proxies
+            .map(|proxy| match proxy.trim() {
+                proxy if proxy.eq_ignore_ascii_case("direct") => None,
+                proxy => Some(proxy.to_string())
+            })
+            .collect::<Vec<_>>());
+    }
+
+    config
+        .retry_cached_failures(!matches.is_present("no_retry_failures"))
+        .build()
+}
+
+/// Makes the [LyricsFetcher] requested in [matches], which moves [bar] along as it
+/// fetches and records each song in the run's checkpoint.
+fn make_lyrics_fetcher(matches: &ArgMatches, bar: &ProgressBar) -> Result<impl LyricsFetcher, CommandError> {
+    let config = get_fetcher_config(matches)?;
+
+    // Each fetch is logged when there's no progress bar to show for it instead.
+    let mut middlewares: Vec<Box<dyn LyricsMiddleware>> = match bar.is_hidden() {
+        true => vec![Box::new(LoggingMiddleware)],
+        false => vec![Box::new(ProgressMiddleware::new(bar.clone()))]
+    };
+
+    if let Some(path) = get_checkpoint_path(matches) {
+        let checkpoint = CheckpointMiddleware::open(path, matches.is_present("resume"))
+            .map_err(CommandError::Runtime)?;
+
+        middlewares.push(Box::new(checkpoint));
+    }
+
+    Ok(lyrics::make_lyrics_fetcher_with_config(&config, middlewares))
+}
+
+/// Makes the bar a run's fetches are tracked on, sized to [songs] if it's known
+/// how many there are up front.
+fn make_progress_bar(matches: &ArgMatches, songs: &Songs) -> ProgressBar {
+    let bar = progress::make_progress_bar(!matches.is_present("no_progress"));
+
+    if let (min, Some(max)) = songs.size_hint() {
+        if min == max {
+            progress::set_progress_length(&bar, max);
+        }
+    }
+
+    bar
+}
+
+/// The checkpoint file requested in [matches], if the run should be checkpointed.
+pub fn get_checkpoint_path<'a>(matches: &'a ArgMatches) -> Option<&'a str> {
+    match matches.is_present("checkpoint") || matches.is_present("resume") {
+        true => Some(matches.value_of("checkpoint").unwrap_or(CHECKPOINT_LOCATION)),
+        false => None
+    }
+}
+
+/// Wraps up the run requested in [matches] once fetching has been [fetched] (see
+/// [finish_fetching]) and its results [written]: writes its failure report if one
+/// was asked for, even if either went wrong, and works out the code lyrical should
+/// exit with from [summary].
+pub fn finish_run(
+    matches: &ArgMatches,
+    summary: &RunSummary,
+    fetched: Result<(), CommandError>,
+    written: io::Result<()>) -> Result<i32, CommandError> {
+    if let Some(path) = matches.value_of("failure_report") {
+        let report = FailureReport::new(summary.stats(), &summary.failures);
+
+        File::create(path)
+            .map(BufWriter::new)
+            .and_then(|mut out| report::write_failure_report(&mut out, &report).and_then(|_| out.flush()))
+            .map_err(|err| CommandError::Runtime(format!("Failed to write failure report {}: {}", path, err)))?;
+    }
+
+    let fail_under = matches.value_of("fail_under")
+        .and_then(|fail_under| fail_under.trim().parse::<f64>().ok());
+
+    if let Err(err) = &written {
+        eprintln!("Failed to write results: {}", err);
+    }
+
+    fetched?;
+
+    if written.is_err() {
+        return Ok(EXIT_ERROR);
+    }
+
+    match interrupt::interrupted() {
+        true => Ok(EXIT_INTERRUPTED),
+        false => Ok(summary.exit_code(fail_under))
+    }
+}
+
+/// Wraps up fetching for the run requested in [matches]: its checkpoint (if any) is
+/// kept if it was interrupted so it can be resumed, and removed otherwise.
+fn finish_fetching(matches: &ArgMatches, bar: &ProgressBar) -> Result<(), CommandError> {
+    bar.finish_and_clear();
+
+    match (interrupt::interrupted(), get_checkpoint_path(matches)) {
+        (true, Some(CHECKPOINT_LOCATION)) => {
+            eprintln!("Stopped early; run again with --resume to pick up where this run left off.");
+            Ok(())
+        },
+        (true, Some(path)) => {
+            eprintln!("Stopped early; run again with --checkpoint {} --resume to pick up where this run left off.", path);
+            Ok(())
+        },
+        (true, None) => {
+            eprintln!("Stopped early; use --checkpoint to be able to resume an interrupted run.");
+            Ok(())
+        },
+        (false, Some(path)) => checkpoint::remove_checkpoint(path).map_err(CommandError::Runtime),
+        (false, None) => Ok(())
+    }
+}
+
+/// Opens the file requested in [matches] to write to, or stdout.
+fn get_output(matches: &ArgMatches) -> Result<Box<dyn Write>, CommandError> {
+    match matches.value_of("out") {
+        Some(path) => File::create(path)
+            .map(|file| Box::new(BufWriter::new(file)) as Box<dyn Write>)
+            .map_err(|err| CommandError::Runtime(format!("Failed to create {}: {}", path, err))),
+        None => Ok(Box::new(BufWriter::new(io::stdout())))
+    }
+}
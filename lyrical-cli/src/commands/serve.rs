@@ -0,0 +1,161 @@
+use std::time::Duration;
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+use serde::Serialize;
+use tiny_http::{Header, Response, Server};
+
+use liblyrical::lyrics::{FetchError, LyricsFetcher, SongDescriptor};
+use liblyrical::metrics;
+use liblyrical::word_count::{WordCountFilter, WordCountOptions};
+
+use super::*;
+use crate::report::Report;
+
+/// Where `serve` listens unless told otherwise.
+const DEFAULT_ADDR: &str = "127.0.0.1:8080";
+
+/// How long `serve` waits for a request before checking whether it's been interrupted.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The `serve` subcommand: fetching and counting over HTTP.
+///
+/// This is separate from lyrical-api on purpose: it serves the fetcher the CLI's
+/// args set up (its cache file, proxies and retries, shared by every request for
+/// as long as it runs) and responds with the same JSON `count --output json`
+/// writes, rather than lyrical-api's per-request fetchers and response shapes. It
+/// blocks on each request like the rest of the CLI, so it doesn't need an async
+/// runtime either.
+pub fn serve_subcommand<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("serve")
+        .about("Serves lyrics and word counts over HTTP: POST a JSON list of songs to /fetch or /count, or GET /metrics")
+        .arg(Arg::with_name("addr")
+            .long("addr")
+            .value_name("ADDR")
+            .takes_value(true)
+            .help("Sets the address to listen on (default 127.0.0.1:8080)"))
+        .args(&fetcher_config_args())
+        .args(&word_count_args())
+}
+
+/// Runs the `serve` subcommand with the args in [matches] until lyrical is interrupted.
+pub fn run_serve(matches: &ArgMatches) -> Result<i32, CommandError> {
+    let options = get_word_count_options(matches)?;
+    let config = get_fetcher_config(matches)?;
+    let addr = matches.value_of("addr").unwrap_or(DEFAULT_ADDR);
+
+    let server = Server::http(addr)
+        .map_err(|err| CommandError::Runtime(format!("Failed to listen on {}: {}", addr, err)))?;
+
+    // Requests are handled one at a time with the same fetcher, so they share its
+    // cache, which is written back once the server stops.
+    let mut fetcher = lyrics::make_lyrics_fetcher_with_config(&config, vec![Box::new(LoggingMiddleware)]);
+
+    eprintln!("Listening on http://{}; press Ctrl-C to stop", addr);
+
+    while !interrupt::interrupted() {
+        let mut request = match server.recv_timeout(POLL_INTERVAL) {
+            Ok(Some(request)) => request,
+            Ok(None) => continue,
+            Err(err) => return Err(CommandError::Runtime(format!("Failed to receive request: {}", err)))
+        };
+
+        let mut body = String::new();
+        let response = match request.as_reader().read_to_string(&mut body) {
+            Ok(_) => respond(&request.method().to_string(), request.url(), &body, &mut fetcher, &options),
+            Err(err) => ServeResponse::error(400, format!("Failed to read request body: {}", err))
+        };
+
+        let content_type = Header::from_bytes("Content-Type", response.content_type).unwrap();
+        let responded = request.respond(Response::from_string(response.body)
+            .with_status_code(response.status)
+            .with_header(content_type));
+
+        if let Err(err) = responded {
+            eprintln!("Failed to send response: {}", err);
+        }
+    }
+
+    Ok(EXIT_SUCCESS)
+}
+
+/// A response from the `serve` subcommand.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ServeResponse {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl ServeResponse {
+    fn json<T: Serialize>(value: &T) -> Self {
+        ServeResponse {
+            status: 200,
+            content_type: "application/json; charset=utf-8",
+            body: serde_json::to_string(value).unwrap(),
+        }
+    }
+
+    fn error(status: u16, message: String) -> Self {
+        ServeResponse { status, ..ServeResponse::json(&serde_json::json!({ "error": message })) }
+    }
+}
+
+/// The lyrics of a song as `POST /fetch` responds with them.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FetchedSong<'a> {
+    pub song: &'a SongDescriptor,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lyrics: Option<&'a str>,
+    /// Why the song's lyrics couldn't be fetched, if so.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<&'a FetchError>,
+}
+
+impl<'a> FetchedSong<'a> {
+    fn new(song: &'a SongDescriptor, lyrics: &'a LyricsResult) -> Self {
+        FetchedSong { song, lyrics: lyrics.as_deref().ok(), error: lyrics.as_ref().err() }
+    }
+}
+
+/// Responds to a [method] request for [url] with [body], fetching with [fetcher]
+/// and counting words using [options]:
+///
+/// - `POST /fetch` fetches the lyrics of a JSON list of songs, responding with a
+///   [FetchedSong] for each.
+/// - `POST /count` counts the words of a JSON list of songs, responding with the
+///   same [Report] as `count --output json`.
+/// - `GET /metrics` responds with the fetcher and cache metrics in the Prometheus
+///   text format.
+pub fn respond(method: &str, url: &str, body: &str, fetcher: &mut dyn LyricsFetcher, options: &WordCountOptions) -> ServeResponse {
+    let path = url.split('?').next().unwrap_or(url);
+
+    match (method, path) {
+        ("POST", "/fetch") => match input::parse_json_songs(body) {
+            Ok(songs) => {
+                let lyrics = fetch_lyrics_for_songs(fetcher, &songs);
+
+                ServeResponse::json(&lyrics.iter()
+                    .map(|(song, lyrics)| FetchedSong::new(song, lyrics))
+                    .collect::<Vec<_>>())
+            },
+            Err(err) => ServeResponse::error(400, err)
+        },
+        ("POST", "/count") => match input::parse_json_songs(body) {
+            Ok(songs) => {
+                let lyrics = fetch_lyrics_for_songs(fetcher, &songs);
+                let word_counts = count_words_for_lyrics(&lyrics, options);
+
+                ServeResponse::json(&Report::new(&word_counts, &[], &WordCountFilter::default()))
+            },
+            Err(err) => ServeResponse::error(400, err)
+        },
+        ("GET", "/metrics") => ServeResponse {
+            status: 200,
+            content_type: "text/plain; version=0.0.4; charset=utf-8",
+            body: metrics::global().render_prometheus(),
+        },
+        (_, "/fetch") | (_, "/count") | (_, "/metrics") =>
+            ServeResponse::error(405, format!("{} isn't allowed for {}", method, path)),
+        _ => ServeResponse::error(404, format!("Nothing at {}", path))
+    }
+}
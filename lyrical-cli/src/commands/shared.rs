@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::io;
+use std::io::Write;
+
+use liblyrical::analysis;
+use liblyrical::analysis::{Explicitness, GroupBy, ProfanityFilter};
+use liblyrical::lyrics::{FetchError, LyricsFetcher, SongDescriptor};
+use liblyrical::word_count;
+use liblyrical::word_count::{WordCountFilter, WordCountOptions, WordCounts};
+
+use crate::input::Songs;
+use crate::interrupt;
+use crate::outcome::RunSummary;
+use crate::report;
+use crate::report::SongRecord;
+
+pub type LyricsResult = Result<String, FetchError>;
+pub type SongLyricsResult<'a> = (&'a SongDescriptor, LyricsResult);
+pub type WordCountsResult = Result<WordCounts, String>;
+pub type SongWordCountsResult<'a> = (&'a SongDescriptor, WordCountsResult);
+
+/// Collects [songs], skipping (and reporting) the ones that couldn't be read.
+pub fn collect_songs(songs: Songs) -> Vec<SongDescriptor> {
+    songs
+        .filter_map(|song| song.map_err(|err| eprintln!("Skipping song: {}", err)).ok())
+        .collect()
+}
+
+/// Rates how explicit [lyrics] are using [profanity_filter].
+pub fn get_explicitness(lyrics: &str, profanity_filter: &ProfanityFilter) -> Explicitness {
+    profanity_filter.explicitness(&word_count::count_words(lyrics.to_string()))
+}
+
+/// Splits [lyrics] into the songs that aren't explicit and the ones that are.
+pub fn exclude_explicit_songs<'a>(lyrics: Vec<SongLyricsResult<'a>>, profanity_filter: &ProfanityFilter) -> (Vec<SongLyricsResult<'a>>, Vec<&'a SongDescriptor>) {
+    lyrics.into_iter()
+        .fold((vec![], vec![]), |mut acc, (song, lyrics)| {
+            match &lyrics {
+                Ok(text) if get_explicitness(text, profanity_filter).explicit => acc.1.push(song),
+                _ => acc.0.push((song, lyrics))
+            };
+
+            acc
+        })
+}
+
+/// Masks the profane words in each of the successful [word_counts].
+pub fn mask_profanity<'a>(word_counts: Vec<SongWordCountsResult<'a>>, profanity_filter: &ProfanityFilter) -> Vec<SongWordCountsResult<'a>> {
+    word_counts.into_iter()
+        .map(|(song, counts)| (song, counts.map(|counts| profanity_filter.mask_word_counts(counts))))
+        .collect()
+}
+
+/// Gets the (up to) [limit] most distinctive words of each group of songs in
+/// [word_counts], grouped by [group_by].
+pub fn get_distinctive_words(word_counts: &[SongWordCountsResult], limit: usize, group_by: GroupBy) -> HashMap<String, Vec<(String, f64)>> {
+    let groups = analysis::group_word_counts(
+        word_counts.iter().filter_map(|(song, counts)| counts.clone().ok().map(|counts| (*song, counts))),
+        |song| group_by.key(song));
+
+    analysis::distinctive_words(&groups, limit)
+}
+
+/// Fetches the lyrics for each of [songs] using [fetcher], skipping the rest once
+/// lyrical is interrupted.
+pub fn fetch_lyrics_for_songs<'a>(fetcher: &mut dyn LyricsFetcher, songs: &'a [SongDescriptor]) -> Vec<SongLyricsResult<'a>> {
+    songs.iter()
+        .map(|song| (song, interrupt::fetch_lyrics(fetcher, song)))
+        .collect()
+}
+
+/// Fetches and counts the words of each of [songs] as they're read, writing each
+/// song's [SongRecord] to [out] as soon as it's done; songs that couldn't be read
+/// get an invalid record.
+///
+/// Explicit songs are left out if [exclude_explicit] is set and profane words are
+/// masked if [mask] is. Once lyrical is interrupted, the rest of the songs are
+/// skipped. Stops early if writing fails, and returns a summary of the songs
+/// fetched until then along with how writing went.
+pub fn stream_word_counts(
+    out: &mut dyn Write,
+    fetcher: &mut dyn LyricsFetcher,
+    songs: Songs,
+    options: &WordCountOptions,
+    filter: &WordCountFilter,
+    exclude_explicit: Option<&ProfanityFilter>,
+    mask: Option<&ProfanityFilter>) -> (RunSummary, io::Result<()>) {
+    let mut summary = RunSummary::default();
+
+    for song in songs {
+        let song = match song {
+            Ok(song) => song,
+            Err(err) => {
+                eprintln!("Skipping song: {}", err);
+                if let Err(err) = report::write_song_record(out, &SongRecord::invalid(&err)) {
+                    return (summary, Err(err));
+                }
+
+                continue;
+            }
+        };
+
+        let lyrics = vec![(&song, interrupt::fetch_lyrics(fetcher, &song))];
+        summary.record(&song, &lyrics[0].1);
+
+        let (lyrics, explicit_songs) = match exclude_explicit {
+            Some(profanity_filter) => exclude_explicit_songs(lyrics, profanity_filter),
+            None => (lyrics, vec![])
+        };
+
+        let mut word_counts = count_words_for_lyrics(&lyrics, options);
+
+        if let Some(profanity_filter) = mask {
+            word_counts = mask_profanity(word_counts, profanity_filter);
+        }
+
+        let record = match word_counts.first() {
+            Some(word_counts) => SongRecord::new(word_counts, filter),
+            None => SongRecord::excluded(explicit_songs[0])
+        };
+
+        if let Err(err) = report::write_song_record(out, &record) {
+            return (summary, Err(err));
+        }
+    }
+
+    (summary, Ok(()))
+}
+
+/// Counts the words in each of the fetched [lyrics] using [options].
+pub fn count_words_for_lyrics<'a>(lyrics: &[SongLyricsResult<'a>], options: &WordCountOptions) -> Vec<SongWordCountsResult<'a>> {
+    lyrics.iter()
+        .map(|(song, lyrics)| (*song, lyrics.as_ref()
+            .map(|lyrics| word_count::count_words_with_options(lyrics, options))
+            .map_err(|err| err.to_string())))
+        .collect()
+}
+
+/// Counts the phrases of each length in [sizes] in each of the fetched [lyrics]
+/// using [options].
+pub fn count_phrases_for_lyrics<'a>(lyrics: &[SongLyricsResult<'a>], sizes: &[usize], options: &WordCountOptions) -> Vec<SongWordCountsResult<'a>> {
+    lyrics.iter()
+        .map(|(song, lyrics)| (*song, lyrics.as_ref()
+            .map(|lyrics| word_count::count_phrases(lyrics, sizes, options))
+            .map_err(|err| err.to_string())))
+        .collect()
+}
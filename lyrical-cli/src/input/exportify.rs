@@ -0,0 +1,58 @@
+use liblyrical::lyrics::SongDescriptor;
+
+use super::join_artists;
+
+/// The headers of the track name column.
+const NAME_HEADERS: [&str; 1] = ["Track Name"];
+/// The headers of the artist names column, in current and older exports.
+const ARTIST_HEADERS: [&str; 2] = ["Artist Name(s)", "Artist Name"];
+
+/// Reads the songs in a CSV playlist export from Exportify.
+///
+/// Songs by several artists list them separated by commas, with commas inside an
+/// artist's name escaped ("Tyler\, The Creator").
+pub fn parse_exportify_songs(input: &str) -> Result<Vec<SongDescriptor>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(input.as_bytes());
+
+    let headers = reader.headers()
+        .map_err(|err| format!("Failed to read Exportify CSV headers: {}", err))?
+        .clone();
+
+    let column = |candidates: &[&str]| headers.iter()
+        .position(|header| candidates.iter().any(|candidate| header.trim() == *candidate))
+        .ok_or_else(|| format!("Exportify CSV is missing a \"{}\" column", candidates[0]));
+
+    let name_column = column(&NAME_HEADERS)?;
+    let artist_column = column(&ARTIST_HEADERS)?;
+
+    reader.records()
+        .map(|record| {
+            let record = record.map_err(|err| format!("Failed to read Exportify CSV: {}", err))?;
+            let field = |column: usize| record.get(column).unwrap_or("").to_string();
+
+            Ok(SongDescriptor {
+                name: field(name_column).trim().to_string(),
+                artist: join_artists(split_artists(&field(artist_column))),
+                uri: None,
+            })
+        })
+        .collect()
+}
+
+/// Splits [artists] on the commas that aren't escaped with a backslash.
+fn split_artists(artists: &str) -> Vec<String> {
+    let mut split = vec![String::new()];
+    let mut chars = artists.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&',') => split.last_mut().unwrap().push(chars.next().unwrap()),
+            ',' => split.push(String::new()),
+            c => split.last_mut().unwrap().push(c)
+        }
+    }
+
+    split
+}
@@ -0,0 +1,55 @@
+use roxmltree::{Document, Node, ParsingOptions};
+
+use liblyrical::lyrics::SongDescriptor;
+
+/// Track properties that mark a library entry as something other than a song.
+const NON_SONG_KEYS: [&str; 3] = ["Podcast", "Movie", "TV Show"];
+
+/// Reads the songs in an Apple Music/iTunes XML library export (File > Library >
+/// Export Library...), in the order they're listed.
+pub fn parse_itunes_songs(input: &str) -> Result<Vec<SongDescriptor>, String> {
+    // Library exports start with the plist DOCTYPE.
+    let document = Document::parse_with_options(input, ParsingOptions { allow_dtd: true })
+        .map_err(|err| format!("Failed to parse provided iTunes library: {}", err))?;
+
+    let library = document.root_element()
+        .children()
+        .find(|node| node.has_tag_name("dict"))
+        .ok_or_else(|| "Failed to parse provided iTunes library: expected a plist dictionary".to_string())?;
+
+    let tracks = dict_entries(library)
+        .into_iter()
+        .find(|(key, _)| key == "Tracks")
+        .map(|(_, tracks)| tracks)
+        .ok_or_else(|| "Failed to parse provided iTunes library: it doesn't list any tracks".to_string())?;
+
+    Ok(dict_entries(tracks)
+        .into_iter()
+        .map(|(_, track)| dict_entries(track))
+        .filter(|track| !track.iter().any(|(key, value)| NON_SONG_KEYS.contains(&key.as_str()) && value.has_tag_name("true")))
+        .map(|track| {
+            let field = |name: &str| track.iter()
+                .find(|(key, _)| key == name)
+                .and_then(|(_, value)| value.text())
+                .unwrap_or("")
+                .trim()
+                .to_string();
+
+            SongDescriptor { name: field("Name"), artist: field("Artist"), uri: None }
+        })
+        .collect())
+}
+
+/// The keys and values of a plist `<dict>`, in order.
+fn dict_entries<'a, 'input>(dict: Node<'a, 'input>) -> Vec<(String, Node<'a, 'input>)> {
+    let children = dict.children()
+        .filter(|node| node.is_element())
+        .collect::<Vec<_>>();
+
+    children.chunks(2)
+        .filter_map(|entry| match entry {
+            [key, value] if key.has_tag_name("key") => Some((key.text().unwrap_or("").to_string(), *value)),
+            _ => None
+        })
+        .collect()
+}
@@ -0,0 +1,39 @@
+use super::PlaylistEntry;
+
+/// Reads the entries of an M3U/M3U8 playlist, taking their metadata from the
+/// `#EXTINF:<duration>,<Artist> - <Title>` line before them, if any.
+pub fn parse_m3u_entries(input: &str) -> Vec<PlaylistEntry> {
+    let mut entries = vec![];
+    let mut title = None;
+
+    for line in input.lines().map(|line| line.trim_start_matches('\u{feff}').trim()) {
+        if line.is_empty() {
+            continue;
+        }
+
+        match line.strip_prefix("#EXTINF:") {
+            Some(info) => title = extinf_title(info),
+            None if line.starts_with('#') => {},
+            None => entries.push(PlaylistEntry { location: Some(line.to_string()), title: title.take(), artist: None })
+        }
+    }
+
+    entries
+}
+
+/// The title after the duration (and any `key="value"` attributes) of an
+/// `#EXTINF` line.
+fn extinf_title(info: &str) -> Option<String> {
+    let mut quoted = false;
+
+    info.char_indices()
+        .find(|(_, c)| {
+            if *c == '"' {
+                quoted = !quoted;
+            }
+
+            *c == ',' && !quoted
+        })
+        .map(|(comma, _)| info[comma + 1..].trim().to_string())
+        .filter(|title| !title.is_empty())
+}
@@ -0,0 +1,192 @@
+mod exportify;
+mod itunes;
+mod m3u;
+mod playlist;
+mod pls;
+mod spotify;
+mod xspf;
+
+use std::io::BufRead;
+use std::path::Path;
+use std::str::FromStr;
+
+use liblyrical::lyrics::SongDescriptor;
+
+pub use exportify::*;
+pub use itunes::*;
+pub use m3u::*;
+pub use playlist::*;
+pub use pls::*;
+pub use spotify::*;
+pub use xspf::*;
+
+/// Songs read from an input, as they're needed; entries that can't be read are errors.
+pub type Songs = Box<dyn Iterator<Item = Result<SongDescriptor, String>>>;
+
+/// The formats songs to fetch can be read from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputFormat {
+    /// A JSON array of [SongDescriptor]s.
+    Json,
+    /// One JSON [SongDescriptor] per line.
+    Ndjson,
+    /// One "Artist - Title" per line.
+    Text,
+    /// A Spotify playlist, either from an account data export or the Web API.
+    Spotify,
+    /// A CSV playlist export from Exportify.
+    Exportify,
+    /// An Apple Music/iTunes XML library export.
+    Itunes,
+    /// An M3U or M3U8 playlist.
+    M3u,
+    /// A PLS playlist.
+    Pls,
+    /// An XSPF (XML Shareable Playlist Format) playlist.
+    Xspf,
+}
+
+impl InputFormat {
+    pub const ALL: [InputFormat; 9] = [
+        InputFormat::Json,
+        InputFormat::Ndjson,
+        InputFormat::Text,
+        InputFormat::Spotify,
+        InputFormat::Exportify,
+        InputFormat::Itunes,
+        InputFormat::M3u,
+        InputFormat::Pls,
+        InputFormat::Xspf,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            InputFormat::Json => "json",
+            InputFormat::Ndjson => "ndjson",
+            InputFormat::Text => "text",
+            InputFormat::Spotify => "spotify",
+            InputFormat::Exportify => "exportify",
+            InputFormat::Itunes => "itunes",
+            InputFormat::M3u => "m3u",
+            InputFormat::Pls => "pls",
+            InputFormat::Xspf => "xspf",
+        }
+    }
+
+    /// Whether each line of the input is a song of its own, so songs can be read
+    /// one at a time.
+    pub fn is_line_based(&self) -> bool {
+        matches!(self, InputFormat::Ndjson | InputFormat::Text)
+    }
+}
+
+impl FromStr for InputFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        let format = match format.trim().to_lowercase().as_str() {
+            "m3u8" => "m3u".to_string(),
+            format => format.to_string()
+        };
+
+        InputFormat::ALL.iter()
+            .find(|candidate| candidate.name() == format)
+            .cloned()
+            .ok_or_else(|| format!(
+                "Unknown input format \"{}\"; expected one of: {}",
+                format,
+                InputFormat::ALL.iter().map(|format| format.name()).collect::<Vec<_>>().join(", ")))
+    }
+}
+
+/// Reads the songs listed in [input] as [format]; relative paths in playlists are
+/// resolved against [base_dir].
+///
+/// Entries in exports and playlists that lack the artist or name needed to look up
+/// lyrics (like podcasts, or files without tags) are skipped.
+pub fn parse_songs(input: &str, format: InputFormat, base_dir: &Path) -> Result<Vec<SongDescriptor>, String> {
+    let songs = match format {
+        InputFormat::Json => return parse_json_songs(input),
+        InputFormat::Ndjson | InputFormat::Text => return input.lines()
+            .filter_map(|line| parse_song_line(line, format))
+            .collect(),
+        InputFormat::Spotify => parse_spotify_songs(input)?,
+        InputFormat::Exportify => parse_exportify_songs(input)?,
+        InputFormat::Itunes => parse_itunes_songs(input)?,
+        InputFormat::M3u => playlist_songs(parse_m3u_entries(input), base_dir),
+        InputFormat::Pls => playlist_songs(parse_pls_entries(input), base_dir),
+        InputFormat::Xspf => playlist_songs(parse_xspf_entries(input)?, base_dir),
+    };
+
+    Ok(songs.into_iter()
+        .filter(|song| !song.name.is_empty() && !song.artist.is_empty())
+        .collect())
+}
+
+/// Reads the songs in [reader] as [format]. Songs in line-based formats are read
+/// as they're needed, so they can be processed as soon as they come in; other
+/// formats are read in full first.
+pub fn read_songs<R: BufRead + 'static>(mut reader: R, format: InputFormat, base_dir: &Path) -> Result<Songs, String> {
+    if format.is_line_based() {
+        return Ok(Box::new(reader.lines()
+            .enumerate()
+            .filter_map(move |(i, line)| match line {
+                Ok(line) => parse_song_line(&line, format)
+                    .map(|song| song.map_err(|err| format!("Line {}: {}", i + 1, err))),
+                Err(err) => Some(Err(format!("Failed to read line {}: {}", i + 1, err)))
+            })));
+    }
+
+    let mut input = String::new();
+    reader.read_to_string(&mut input)
+        .map_err(|err| format!("Failed to read input: {}", err))?;
+
+    Ok(Box::new(parse_songs(&input, format, base_dir)?.into_iter().map(Ok)))
+}
+
+/// Reads the song on a [line] of a line-based [format]; blank lines (and comments
+/// starting with `#` in text) don't hold a song.
+pub fn parse_song_line(line: &str, format: InputFormat) -> Option<Result<SongDescriptor, String>> {
+    let line = line.trim();
+
+    match format {
+        _ if line.is_empty() => None,
+        InputFormat::Text if line.starts_with('#') => None,
+        InputFormat::Text => Some(split_artist_and_title(line)
+            .ok_or_else(|| format!("Expected \"Artist - Title\" but got \"{}\"", line))),
+        InputFormat::Ndjson => Some(serde_json::from_str::<SongDescriptor>(line)
+            .map_err(|err| format!("Failed to parse song \"{}\": {}", line, err))),
+        _ => Some(Err(format!("{} input can't be read line by line", format.name())))
+    }
+}
+
+/// Reads a JSON array of [SongDescriptor]s.
+pub fn parse_json_songs(input: &str) -> Result<Vec<SongDescriptor>, String> {
+    serde_json::from_str::<Vec<SongDescriptor>>(input)
+        .map_err(|err| {
+            let example = vec![
+                SongDescriptor{ name: "foo".into(), artist: "bar".into(), uri:  None }
+            ];
+
+            format!(
+                "Failed to parse provided json ({}): please make sure it's in the following format: {}",
+                err,
+                serde_json::to_string(&example).unwrap())
+        })
+}
+
+fn playlist_songs(entries: Vec<PlaylistEntry>, base_dir: &Path) -> Vec<SongDescriptor> {
+    entries.iter()
+        .filter_map(|entry| entry.to_song(base_dir))
+        .collect()
+}
+
+/// Joins the names of a song's [artists] the way they're written in song descriptors
+/// ("Daft Punk, Pharrell Williams").
+fn join_artists<I: IntoIterator<Item = S>, S: AsRef<str>>(artists: I) -> String {
+    artists.into_iter()
+        .map(|artist| artist.as_ref().trim().to_string())
+        .filter(|artist| !artist.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,119 @@
+use std::path::{Path, PathBuf};
+
+use id3::TagLike;
+
+use liblyrical::lyrics::SongDescriptor;
+
+/// A track as listed in a playlist file, before it's known which song it is.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlaylistEntry {
+    /// Where the track lives: a path (relative to the playlist) or a URL.
+    pub location: Option<String>,
+    /// The track's title, or "Artist - Title" if [artist] isn't set.
+    pub title: Option<String>,
+    pub artist: Option<String>,
+}
+
+impl PlaylistEntry {
+    /// Works out which song [self] is from its metadata, falling back to the tags of
+    /// its audio file (if it's a local file) and then its file name ("Artist - Title.mp3").
+    ///
+    /// Relative locations are resolved against [base_dir].
+    pub fn to_song(&self, base_dir: &Path) -> Option<SongDescriptor> {
+        let title = self.title.as_deref().map(str::trim).filter(|title| !title.is_empty());
+        let artist = self.artist.as_deref().map(str::trim).filter(|artist| !artist.is_empty());
+
+        match (artist, title) {
+            (Some(artist), Some(title)) => Some(make_song(artist, title)),
+            (None, Some(title)) if title.contains(" - ") => split_artist_and_title(title),
+            _ => {
+                self.local_path(base_dir)
+                    .as_deref()
+                    .and_then(read_audio_tags)
+                    .or_else(|| self.location.as_deref()
+                        .and_then(file_stem)
+                        .and_then(|stem| split_artist_and_title(&stem)))
+            }
+        }
+    }
+
+    /// The path of the file at [location], if it's a local one.
+    fn local_path(&self, base_dir: &Path) -> Option<PathBuf> {
+        let location = self.location.as_deref()?.trim();
+
+        let path = match location.strip_prefix("file://") {
+            Some(path) => percent_decode(path),
+            None if location.contains("://") => return None,
+            None => location.to_string()
+        };
+
+        Some(base_dir.join(path))
+    }
+}
+
+/// Reads the song an audio file is tagged as; only files with ID3 tags (like MP3s)
+/// are supported.
+pub fn read_audio_tags(path: &Path) -> Option<SongDescriptor> {
+    let tag = id3::v1v2::read_from_path(path).ok()?;
+
+    match (tag.artist(), tag.title()) {
+        (Some(artist), Some(title)) if !artist.trim().is_empty() && !title.trim().is_empty() =>
+            Some(make_song(artist.trim(), title.trim())),
+        _ => None
+    }
+}
+
+fn make_song(artist: &str, title: &str) -> SongDescriptor {
+    SongDescriptor { name: title.to_string(), artist: artist.to_string(), uri: None }
+}
+
+/// Splits "Artist - Title" at its first " - ", since titles often have one of
+/// their own ("I Won't Fall Apart - Eurobeat Version").
+pub fn split_artist_and_title(text: &str) -> Option<SongDescriptor> {
+    let separator = text.find(" - ")?;
+    let (artist, title) = (text[..separator].trim(), text[separator + 3..].trim());
+
+    match artist.is_empty() || title.is_empty() {
+        true => None,
+        false => Some(make_song(artist, title))
+    }
+}
+
+/// The file name of [location] without its extension.
+fn file_stem(location: &str) -> Option<String> {
+    let name = location.trim().rsplit(['/', '\\']).next()?;
+    let name = percent_decode(name);
+
+    match name.rfind('.') {
+        Some(extension) if extension > 0 => Some(name[..extension].to_string()),
+        _ => Some(name)
+    }
+}
+
+/// Decodes the `%XX` escapes in a URL [path].
+fn percent_decode(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let escaped = match bytes[i] {
+            b'%' if i + 2 < bytes.len() =>
+                std::str::from_utf8(&bytes[i + 1..i + 3]).ok().and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+            _ => None
+        };
+
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            },
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -0,0 +1,30 @@
+use std::collections::BTreeMap;
+
+use super::PlaylistEntry;
+
+/// Reads the entries of a PLS playlist (`File1=...`, `Title1=Artist - Title`), in
+/// the order of their numbers.
+pub fn parse_pls_entries(input: &str) -> Vec<PlaylistEntry> {
+    let entries = input.lines()
+        .filter_map(|line| {
+            let separator = line.find('=')?;
+            let (key, value) = (line[..separator].trim(), line[separator + 1..].trim());
+            let digits = key.find(|c: char| c.is_ascii_digit())?;
+            let index = key[digits..].parse::<usize>().ok()?;
+
+            Some((key[..digits].to_lowercase(), index, value.to_string()))
+        })
+        .fold(BTreeMap::new(), |mut acc: BTreeMap<usize, PlaylistEntry>, (key, index, value)| {
+            let entry = acc.entry(index).or_default();
+
+            match key.as_str() {
+                "file" => entry.location = Some(value),
+                "title" => entry.title = Some(value),
+                _ => {}
+            };
+
+            acc
+        });
+
+    entries.into_values().collect()
+}
@@ -0,0 +1,127 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use liblyrical::lyrics::{SongDescriptor, SongUri};
+
+use super::join_artists;
+
+/// The shapes Spotify hands out playlists in.
+enum SpotifyExport {
+    /// `Playlist1.json` from an account data export ("Download your data").
+    Account(AccountExport),
+    /// A playlist from the Web API (`GET /playlists/{id}`).
+    Playlist(WebApiPlaylist),
+    /// A page of a playlist's items from the Web API (`GET /playlists/{id}/tracks`).
+    Items(PlaylistItems),
+}
+
+impl SpotifyExport {
+    /// Reads [value] as whichever shape its top-level keys say it is, so that
+    /// errors describe what's wrong with that shape.
+    fn from_value(value: Value) -> Result<Self, String> {
+        let export = if value.get("playlists").is_some() {
+            serde_json::from_value(value).map(SpotifyExport::Account)
+        } else if value.get("tracks").is_some() {
+            serde_json::from_value(value).map(SpotifyExport::Playlist)
+        } else if value.get("items").is_some() {
+            serde_json::from_value(value).map(SpotifyExport::Items)
+        } else {
+            return Err("expected an account data export (Playlist1.json) or a Web API playlist".to_string());
+        };
+
+        export.map_err(|err| err.to_string())
+    }
+}
+
+#[derive(Deserialize)]
+struct AccountExport {
+    playlists: Vec<AccountPlaylist>,
+}
+
+#[derive(Deserialize)]
+struct WebApiPlaylist {
+    tracks: PlaylistItems,
+}
+
+#[derive(Deserialize)]
+struct AccountPlaylist {
+    items: Vec<AccountPlaylistItem>,
+}
+
+#[derive(Deserialize)]
+struct AccountPlaylistItem {
+    /// Missing for podcast episodes.
+    track: Option<AccountTrack>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AccountTrack {
+    track_name: String,
+    artist_name: String,
+    #[serde(default)]
+    track_uri: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PlaylistItems {
+    items: Vec<PlaylistItem>,
+}
+
+#[derive(Deserialize)]
+struct PlaylistItem {
+    /// Missing for tracks that have been taken down.
+    track: Option<Track>,
+}
+
+#[derive(Deserialize)]
+struct Track {
+    name: String,
+    /// Missing for podcast episodes.
+    #[serde(default)]
+    artists: Vec<Artist>,
+    /// Missing for local files.
+    #[serde(default)]
+    uri: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Artist {
+    name: String,
+}
+
+/// Reads the songs in a Spotify playlist export; account data exports can hold
+/// several playlists, whose songs are all read in order.
+pub fn parse_spotify_songs(input: &str) -> Result<Vec<SongDescriptor>, String> {
+    let export = serde_json::from_str::<Value>(input)
+        .map_err(|err| err.to_string())
+        .and_then(SpotifyExport::from_value)
+        .map_err(|err| format!("Failed to parse provided Spotify export: {}", err))?;
+
+    let songs = match export {
+        SpotifyExport::Account(export) => export.playlists.into_iter()
+            .flat_map(|playlist| playlist.items)
+            .filter_map(|item| item.track)
+            .map(|track| SongDescriptor {
+                name: track.track_name,
+                artist: track.artist_name,
+                uri: spotify_uri(track.track_uri)
+            })
+            .collect(),
+        SpotifyExport::Playlist(WebApiPlaylist { tracks: items }) | SpotifyExport::Items(items) => items.items.into_iter()
+            .filter_map(|item| item.track)
+            .map(|track| SongDescriptor {
+                name: track.name,
+                artist: join_artists(track.artists.iter().map(|artist| &artist.name)),
+                uri: spotify_uri(track.uri)
+            })
+            .collect(),
+    };
+
+    Ok(songs)
+}
+
+/// The [SongUri] for a track's [uri], unless it's missing or blank.
+fn spotify_uri(uri: Option<String>) -> Option<SongUri> {
+    uri.filter(|uri| !uri.trim().is_empty()).map(SongUri::SpotifyUri)
+}
@@ -0,0 +1,31 @@
+use roxmltree::{Document, Node};
+
+use super::PlaylistEntry;
+
+/// Reads the entries of an XSPF playlist from each track's `<location>`, `<title>`
+/// and `<creator>`.
+pub fn parse_xspf_entries(input: &str) -> Result<Vec<PlaylistEntry>, String> {
+    let document = Document::parse(input)
+        .map_err(|err| format!("Failed to parse provided XSPF playlist: {}", err))?;
+
+    let track_list = document.root_element()
+        .children()
+        .find(|node| node.has_tag_name("trackList"))
+        .ok_or_else(|| "Failed to parse provided XSPF playlist: it doesn't have a trackList".to_string())?;
+
+    Ok(track_list.children()
+        .filter(|node| node.has_tag_name("track"))
+        .map(|track| PlaylistEntry {
+            location: child_text(track, "location"),
+            title: child_text(track, "title"),
+            artist: child_text(track, "creator"),
+        })
+        .collect())
+}
+
+fn child_text(node: Node, name: &str) -> Option<String> {
+    node.children()
+        .find(|child| child.has_tag_name(name))
+        .and_then(|child| child.text())
+        .map(|text| text.trim().to_string())
+}
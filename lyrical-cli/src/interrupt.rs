@@ -0,0 +1,44 @@
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use liblyrical::lyrics::{FailureCategory, FetchError, LyricsFetcher, LyricsResult, SongDescriptor};
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Makes Ctrl-C stop fetching at the next song instead of killing lyrical, so the
+/// cache still gets written back and the results so far still get reported. A
+/// second Ctrl-C quits straight away.
+pub fn handle_interrupts() {
+    let handled = ctrlc::set_handler(|| {
+        if INTERRUPTED.swap(true, Ordering::SeqCst) {
+            eprintln!("\nInterrupted again; quitting without saving.");
+            process::exit(130);
+        }
+
+        eprintln!("\nInterrupted; finishing the current song (press Ctrl-C again to quit now)...");
+    });
+
+    if let Err(err) = handled {
+        eprintln!("Failed to handle Ctrl-C: {}", err);
+    }
+}
+
+/// Whether Ctrl-C has been pressed.
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// Fetches the lyrics for [song] using [fetcher] unless Ctrl-C has been pressed, in
+/// which case the song is skipped (see [skipped]) so it still counts towards the
+/// run's totals.
+pub fn fetch_lyrics(fetcher: &mut dyn LyricsFetcher, song: &SongDescriptor) -> LyricsResult {
+    match interrupted() {
+        true => Err(skipped()),
+        false => fetcher.fetch_lyrics(song)
+    }
+}
+
+/// The error for a song that wasn't fetched because Ctrl-C was pressed first.
+pub fn skipped() -> FetchError {
+    FetchError::new(FailureCategory::Skipped, "Skipped because lyrical was interrupted")
+}
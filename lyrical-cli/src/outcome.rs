@@ -0,0 +1,103 @@
+use std::fmt;
+
+use liblyrical::lyrics::{FetchError, SongDescriptor};
+
+use crate::report::RunStats;
+use crate::commands::{LyricsResult, SongLyricsResult};
+
+/// Everything went fine.
+pub const EXIT_SUCCESS: i32 = 0;
+/// Something went wrong while running, e.g. the results or the cache couldn't be written.
+pub const EXIT_ERROR: i32 = 1;
+/// The args were invalid, or the input they name couldn't be read.
+pub const EXIT_USAGE: i32 = 2;
+/// Some of the songs couldn't be fetched (more than `--fail-under` allows, if set).
+pub const EXIT_PARTIAL_FAILURE: i32 = 3;
+/// None of the songs could be fetched.
+pub const EXIT_TOTAL_FAILURE: i32 = 4;
+/// The run was stopped early with Ctrl-C.
+pub const EXIT_INTERRUPTED: i32 = 130;
+
+/// Why a command couldn't run.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CommandError {
+    /// The args were invalid, or the input they name couldn't be read.
+    Usage(String),
+    /// Something went wrong while running.
+    Runtime(String),
+}
+
+impl CommandError {
+    /// The code lyrical should exit with after failing with this error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CommandError::Usage(_) => EXIT_USAGE,
+            CommandError::Runtime(_) => EXIT_ERROR,
+        }
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CommandError::Usage(err) | CommandError::Runtime(err) => write!(formatter, "{}", err),
+        }
+    }
+}
+
+/// Most errors come from parsing args, so plain ones are [CommandError::Usage]s.
+impl From<String> for CommandError {
+    fn from(err: String) -> Self {
+        CommandError::Usage(err)
+    }
+}
+
+/// How many songs a run looked up and which of them it couldn't fetch.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RunSummary {
+    pub songs: usize,
+    /// Each song that couldn't be fetched and the error fetching it failed with.
+    pub failures: Vec<(SongDescriptor, FetchError)>,
+}
+
+impl RunSummary {
+    /// Summarizes the fetched [lyrics] of each song.
+    pub fn from_lyrics(lyrics: &[SongLyricsResult]) -> Self {
+        let mut summary = RunSummary::default();
+
+        for (song, lyrics) in lyrics {
+            summary.record(song, lyrics);
+        }
+
+        summary
+    }
+
+    /// Adds the [lyrics] fetched for [song] to the summary.
+    pub fn record(&mut self, song: &SongDescriptor, lyrics: &LyricsResult) {
+        self.songs += 1;
+
+        if let Err(err) = lyrics {
+            self.failures.push((song.clone(), err.clone()));
+        }
+    }
+
+    pub fn stats(&self) -> RunStats {
+        RunStats::new(self.songs, self.failures.len())
+    }
+
+    /// The code lyrical should exit with after this run. Any failure is a partial
+    /// failure unless at least [fail_under] percent of the songs were fetched, but
+    /// a run where every song failed is always a total failure.
+    pub fn exit_code(&self, fail_under: Option<f64>) -> i32 {
+        let stats = self.stats();
+
+        match (stats.songs, stats.failures) {
+            (_, 0) => EXIT_SUCCESS,
+            (songs, failures) if songs == failures => EXIT_TOTAL_FAILURE,
+            _ => match fail_under {
+                Some(fail_under) if stats.success_rate >= fail_under => EXIT_SUCCESS,
+                _ => EXIT_PARTIAL_FAILURE
+            }
+        }
+    }
+}
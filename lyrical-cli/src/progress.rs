@@ -0,0 +1,57 @@
+use std::fmt::Debug;
+
+use indicatif::{ProgressBar, ProgressStyle};
+
+use liblyrical::lyrics::{BeforeFetch, LyricsMiddleware, LyricsResult, SongDescriptor};
+
+const SPINNER_TEMPLATE: &str = "{spinner} [{elapsed_precise}] {pos} songs {wide_msg}";
+const BAR_TEMPLATE: &str = "{spinner} [{elapsed_precise}] {bar:30} {pos}/{len} (ETA {eta}) {wide_msg}";
+
+/// Makes the bar fetches are tracked on, drawn on stderr. It's hidden if [enabled]
+/// isn't set or stderr isn't a terminal.
+pub fn make_progress_bar(enabled: bool) -> ProgressBar {
+    let bar = match enabled {
+        true => ProgressBar::new_spinner(),
+        false => ProgressBar::hidden()
+    };
+
+    bar.set_style(ProgressStyle::default_spinner().template(SPINNER_TEMPLATE));
+    bar
+}
+
+/// Shows how far [bar] is through [len] songs, with an ETA, now that it's known.
+pub fn set_progress_length(bar: &ProgressBar, len: usize) {
+    bar.set_style(ProgressStyle::default_bar().template(BAR_TEMPLATE));
+    bar.set_length(len as u64);
+}
+
+/// A [LyricsMiddleware] that moves a [ProgressBar] along with each fetch.
+pub struct ProgressMiddleware {
+    bar: ProgressBar,
+}
+
+impl ProgressMiddleware {
+    pub fn new(bar: ProgressBar) -> Self {
+        ProgressMiddleware { bar }
+    }
+}
+
+impl LyricsMiddleware for ProgressMiddleware {
+    fn before_fetch(&mut self, song: SongDescriptor) -> BeforeFetch {
+        self.bar.set_message(&format!("{} - {}", song.artist, song.name));
+
+        BeforeFetch::Continue(song)
+    }
+
+    fn after_fetch(&mut self, _song: &SongDescriptor, result: LyricsResult) -> LyricsResult {
+        self.bar.inc(1);
+
+        result
+    }
+}
+
+impl Debug for ProgressMiddleware {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        formatter.write_str("ProgressMiddleware { }")
+    }
+}
@@ -0,0 +1,58 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::io::Write;
+
+use serde::Serialize;
+
+use liblyrical::lyrics::{FailureCategory, FetchError, SongDescriptor};
+
+use super::RunStats;
+
+/// The version of the [FailureReport] schema; bump it whenever a field is renamed or removed.
+pub const FAILURE_REPORT_VERSION: u32 = 1;
+
+/// A song whose lyrics couldn't be fetched, with the broad category of why.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CategorizedFailure {
+    pub song: SongDescriptor,
+    pub category: FailureCategory,
+    pub reason: String,
+}
+
+/// Every song a run couldn't fetch, for CI jobs and scripts to pick over.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FailureReport {
+    pub version: u32,
+    pub stats: RunStats,
+    /// How many failures there were of each category.
+    pub categories: BTreeMap<&'static str, usize>,
+    pub failures: Vec<CategorizedFailure>,
+}
+
+impl FailureReport {
+    /// Builds a [FailureReport] for a run with [stats] from its [failures]: each song
+    /// that couldn't be fetched and the error fetching it failed with.
+    pub fn new(stats: RunStats, failures: &[(SongDescriptor, FetchError)]) -> Self {
+        let failures = failures.iter()
+            .map(|(song, err)| CategorizedFailure {
+                song: song.clone(),
+                category: err.category,
+                reason: err.message.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        let categories = failures.iter()
+            .fold(BTreeMap::new(), |mut categories, failure| {
+                *categories.entry(failure.category.name()).or_insert(0) += 1;
+                categories
+            });
+
+        FailureReport { version: FAILURE_REPORT_VERSION, stats, categories, failures }
+    }
+}
+
+/// Writes [report] to [out] as pretty-printed JSON.
+pub fn write_failure_report(out: &mut dyn Write, report: &FailureReport) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut *out, report)?;
+    writeln!(out)
+}
@@ -0,0 +1,84 @@
+use std::io;
+use std::io::Write;
+
+use super::{Report, WordCount};
+
+/// Writes [report] to [out] as a Markdown document with a table per section.
+pub fn write_markdown_report(out: &mut dyn Write, report: &Report) -> io::Result<()> {
+    writeln!(out, "# Lyrical Report")?;
+    writeln!(out)?;
+
+    writeln!(out, "| Songs | Successes | Failures | Success rate |")?;
+    writeln!(out, "| ---: | ---: | ---: | ---: |")?;
+    writeln!(
+        out,
+        "| {} | {} | {} | {:.1}% |",
+        report.stats.songs,
+        report.stats.successes,
+        report.stats.failures,
+        report.stats.success_rate)?;
+    writeln!(out)?;
+
+    writeln!(out, "## Most Common Words")?;
+    writeln!(out)?;
+    write_word_counts_table(out, &report.aggregated)?;
+
+    for song in &report.songs {
+        writeln!(out, "## {} - {}", escape(&song.song.artist), escape(&song.song.name))?;
+        writeln!(out)?;
+        write_word_counts_table(out, &song.word_counts)?;
+    }
+
+    if !report.failures.is_empty() {
+        writeln!(out, "## Failures")?;
+        writeln!(out)?;
+
+        for failure in &report.failures {
+            writeln!(out, "- {} - {}: {}", escape(&failure.song.artist), escape(&failure.song.name), escape(&failure.reason))?;
+        }
+
+        writeln!(out)?;
+    }
+
+    if !report.excluded.is_empty() {
+        writeln!(out, "## Excluded Explicit Songs")?;
+        writeln!(out)?;
+
+        for song in &report.excluded {
+            writeln!(out, "- {} - {}", escape(&song.artist), escape(&song.name))?;
+        }
+
+        writeln!(out)?;
+    }
+
+    Ok(())
+}
+
+fn write_word_counts_table(out: &mut dyn Write, word_counts: &[WordCount]) -> io::Result<()> {
+    writeln!(out, "| Word | Count |")?;
+    writeln!(out, "| --- | ---: |")?;
+
+    for word_count in word_counts {
+        writeln!(out, "| {} | {} |", escape(&word_count.word), word_count.count)?;
+    }
+
+    writeln!(out)
+}
+
+/// Escapes the characters in [text] that would otherwise break a table row or
+/// format the text.
+fn escape(text: &str) -> String {
+    text.chars()
+        .fold(String::new(), |mut acc, c| {
+            match c {
+                '|' | '*' | '_' | '`' | '\\' | '[' | ']' | '#' => {
+                    acc.push('\\');
+                    acc.push(c);
+                },
+                '\n' | '\r' => acc.push(' '),
+                _ => acc.push(c)
+            };
+
+            acc
+        })
+}
@@ -0,0 +1,266 @@
+mod failures;
+mod markdown;
+mod tabular;
+mod text;
+
+use std::io;
+use std::io::Write;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use liblyrical::lyrics::SongDescriptor;
+use liblyrical::word_count;
+use liblyrical::word_count::{WordCountFilter, WordCounts};
+
+use crate::commands::SongWordCountsResult;
+
+pub use failures::*;
+pub use markdown::*;
+pub use tabular::*;
+pub use text::*;
+
+/// The version of the [Report] schema; bump it whenever a field is renamed or removed.
+pub const REPORT_VERSION: u32 = 1;
+
+/// The formats a report can be written in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The human-readable report.
+    Text,
+    Json,
+    /// One [SongRecord] per line, written as soon as each song is done.
+    Ndjson,
+    Csv,
+    Tsv,
+    Markdown,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format.trim().to_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "ndjson" => Ok(OutputFormat::Ndjson),
+            "csv" => Ok(OutputFormat::Csv),
+            "tsv" => Ok(OutputFormat::Tsv),
+            "markdown" | "md" => Ok(OutputFormat::Markdown),
+            _ => Err(format!("Unsupported output format \"{}\"; expected text, json, ndjson, csv, tsv or markdown", format))
+        }
+    }
+}
+
+/// A single word and how many times it's used.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct WordCount {
+    pub word: String,
+    pub count: i32,
+}
+
+/// The word counts of a song whose lyrics were fetched.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SongReport {
+    pub song: SongDescriptor,
+    pub word_counts: Vec<WordCount>,
+}
+
+/// A song whose lyrics couldn't be fetched.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Failure {
+    pub song: SongDescriptor,
+    pub reason: String,
+}
+
+/// How a run went overall.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RunStats {
+    /// How many songs were looked up (not counting excluded ones).
+    pub songs: usize,
+    pub successes: usize,
+    pub failures: usize,
+    /// The percentage of songs that were fetched; 0 when there were no songs.
+    pub success_rate: f64,
+}
+
+impl RunStats {
+    /// The stats of a run that looked up [songs] songs, [failures] of which
+    /// couldn't be fetched.
+    pub fn new(songs: usize, failures: usize) -> Self {
+        let successes = songs - failures;
+
+        RunStats {
+            songs,
+            successes,
+            failures,
+            success_rate: match songs {
+                0 => 0f64,
+                songs => successes as f64 * 100f64 / songs as f64
+            },
+        }
+    }
+}
+
+/// The results of a run, in the shape written by the machine-readable [OutputFormat]s.
+///
+/// Word counts only include the words that pass the [WordCountFilter] the report was
+/// built with, in its order.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Report {
+    pub version: u32,
+    pub stats: RunStats,
+    pub songs: Vec<SongReport>,
+    pub aggregated: Vec<WordCount>,
+    pub failures: Vec<Failure>,
+    /// Songs that were left out for being explicit.
+    pub excluded: Vec<SongDescriptor>,
+}
+
+impl Report {
+    /// Builds a [Report] from the [word_counts] of each song that pass [filter] and
+    /// the [excluded] songs.
+    pub fn new(word_counts: &[SongWordCountsResult], excluded: &[&SongDescriptor], filter: &WordCountFilter) -> Self {
+        let mut songs = vec![];
+        let mut failures = vec![];
+
+        for (song, counts) in word_counts {
+            match counts {
+                Ok(counts) => songs.push(SongReport { song: (*song).clone(), word_counts: filtered_word_counts(counts, filter) }),
+                Err(reason) => failures.push(Failure { song: (*song).clone(), reason: reason.clone() })
+            }
+        }
+
+        let aggregated = word_count::aggregate_word_counts(word_counts.iter()
+            .filter_map(|(_, counts)| counts.clone().ok())
+            .collect());
+
+        let stats = RunStats::new(word_counts.len(), failures.len());
+
+        Report {
+            version: REPORT_VERSION,
+            stats,
+            songs,
+            aggregated: filtered_word_counts(&aggregated, filter),
+            failures,
+            excluded: excluded.iter().map(|song| (*song).clone()).collect(),
+        }
+    }
+
+    /// The [SongRecord]s of every song in the report: fetched songs first, then
+    /// failures and excluded songs.
+    pub fn records(&self) -> Vec<SongRecord> {
+        let songs = self.songs.iter()
+            .map(|song| SongRecord {
+                song: Some(song.song.clone()),
+                status: SongStatus::Ok,
+                word_counts: Some(song.word_counts.clone()),
+                reason: None,
+            });
+
+        let failures = self.failures.iter()
+            .map(|failure| SongRecord {
+                song: Some(failure.song.clone()),
+                status: SongStatus::Failed,
+                word_counts: None,
+                reason: Some(failure.reason.clone()),
+            });
+
+        songs.chain(failures)
+            .chain(self.excluded.iter().map(SongRecord::excluded))
+            .collect()
+    }
+}
+
+/// What happened to a song.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SongStatus {
+    /// Its lyrics were fetched and counted.
+    Ok,
+    /// Its lyrics couldn't be fetched.
+    Failed,
+    /// It was left out for being explicit.
+    Excluded,
+    /// The input it was listed in couldn't be read or parsed.
+    Invalid,
+}
+
+/// The results for a single song, as streamed by [OutputFormat::Ndjson].
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SongRecord {
+    /// The song; missing if its input was [SongStatus::Invalid].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub song: Option<SongDescriptor>,
+    pub status: SongStatus,
+    /// The song's word counts that pass the [WordCountFilter], if they were counted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub word_counts: Option<Vec<WordCount>>,
+    /// Why the song's lyrics couldn't be fetched or its input couldn't be read, if so.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl SongRecord {
+    /// Builds a [SongRecord] from a song's [word_counts] that pass [filter].
+    pub fn new(word_counts: &SongWordCountsResult, filter: &WordCountFilter) -> Self {
+        match word_counts {
+            (song, Ok(counts)) => SongRecord {
+                song: Some((*song).clone()),
+                status: SongStatus::Ok,
+                word_counts: Some(filtered_word_counts(counts, filter)),
+                reason: None,
+            },
+            (song, Err(reason)) => SongRecord {
+                song: Some((*song).clone()),
+                status: SongStatus::Failed,
+                word_counts: None,
+                reason: Some(reason.clone()),
+            },
+        }
+    }
+
+    /// Builds the [SongRecord] of a [song] that was left out for being explicit.
+    pub fn excluded(song: &SongDescriptor) -> Self {
+        SongRecord { song: Some(song.clone()), status: SongStatus::Excluded, word_counts: None, reason: None }
+    }
+
+    /// Builds the [SongRecord] of a song whose input couldn't be read or parsed, and why.
+    pub fn invalid(reason: &str) -> Self {
+        SongRecord { song: None, status: SongStatus::Invalid, word_counts: None, reason: Some(reason.to_string()) }
+    }
+}
+
+/// Writes [record] to [out] as a line of JSON and flushes it, so whoever's reading
+/// gets it right away.
+pub fn write_song_record(out: &mut dyn Write, record: &SongRecord) -> io::Result<()> {
+    serde_json::to_writer(&mut *out, record)?;
+    writeln!(out)?;
+
+    out.flush()
+}
+
+fn filtered_word_counts(word_counts: &WordCounts, filter: &WordCountFilter) -> Vec<WordCount> {
+    filter.apply(word_counts)
+        .into_iter()
+        .map(|(word, count)| WordCount { word: word.clone(), count: *count })
+        .collect()
+}
+
+/// Writes [report] to [out] in [format]; [OutputFormat::Text] reports are written
+/// section by section instead (see [write_word_counts_for_songs]).
+pub fn write_report(out: &mut dyn Write, report: &Report, format: OutputFormat) -> io::Result<()> {
+    match format {
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut *out, report)?;
+            writeln!(out)
+        },
+        OutputFormat::Ndjson => report.records()
+            .iter()
+            .try_for_each(|record| write_song_record(out, record)),
+        OutputFormat::Csv => write_delimited_report(out, report, b','),
+        OutputFormat::Tsv => write_delimited_report(out, report, b'\t'),
+        OutputFormat::Markdown => write_markdown_report(out, report),
+        OutputFormat::Text => Err(io::Error::new(io::ErrorKind::InvalidInput, "text reports are written section by section"))
+    }
+}
@@ -0,0 +1,54 @@
+use std::io;
+use std::io::Write;
+
+use super::Report;
+
+/// The columns of delimited reports, in order.
+pub const DELIMITED_REPORT_HEADERS: [&str; 6] = ["kind", "artist", "song", "word", "count", "reason"];
+
+/// Writes [report] to [out] as one record per row, with fields separated by [delimiter].
+///
+/// The `kind` column tells rows apart:
+/// - `stat`: a [super::RunStats] field, named in `word` with its value in `count`.
+/// - `song`: how many times a song uses `word`.
+/// - `total`: how many times all songs together use `word`.
+/// - `failure`: a song that couldn't be fetched, and why.
+/// - `excluded`: a song that was left out for being explicit.
+pub fn write_delimited_report(out: &mut dyn Write, report: &Report, delimiter: u8) -> io::Result<()> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(out);
+
+    writer.write_record(DELIMITED_REPORT_HEADERS)?;
+
+    let stats = [
+        ("songs", report.stats.songs.to_string()),
+        ("successes", report.stats.successes.to_string()),
+        ("failures", report.stats.failures.to_string()),
+        ("success_rate", report.stats.success_rate.to_string()),
+    ];
+
+    for (name, value) in &stats {
+        writer.write_record(["stat", "", "", name, value, ""])?;
+    }
+
+    for song in &report.songs {
+        for word_count in &song.word_counts {
+            writer.write_record(["song", &song.song.artist, &song.song.name, &word_count.word, &word_count.count.to_string(), ""])?;
+        }
+    }
+
+    for word_count in &report.aggregated {
+        writer.write_record(["total", "", "", &word_count.word, &word_count.count.to_string(), ""])?;
+    }
+
+    for failure in &report.failures {
+        writer.write_record(["failure", &failure.song.artist, &failure.song.name, "", "", &failure.reason])?;
+    }
+
+    for song in &report.excluded {
+        writer.write_record(["excluded", &song.artist, &song.name, "", "", ""])?;
+    }
+
+    writer.flush()
+}
@@ -0,0 +1,350 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::io::Write;
+
+use liblyrical::analysis;
+use liblyrical::analysis::{GroupBy, LyricsAnalysis, ProfanityFilter, Sentiment, SentimentAnalyzer, SongSimilarity, VocabularyComparison};
+use liblyrical::lyrics::SongDescriptor;
+use liblyrical::rhyme;
+use liblyrical::rhyme::Pronouncer;
+use liblyrical::word_count;
+use liblyrical::word_count::WordCountFilter;
+
+use super::RunStats;
+use crate::commands::{get_explicitness, SongLyricsResult, SongWordCountsResult};
+
+/// Writes the [explicit_songs] that were left out to [out].
+pub fn write_excluded_songs(out: &mut dyn Write, explicit_songs: &[&SongDescriptor]) -> io::Result<()> {
+    writeln!(out, "Excluded {} explicit songs:", explicit_songs.len())?;
+    for song in explicit_songs {
+        writeln!(out, "\t{:?}", song)?;
+    }
+
+    writeln!(out, "-------")?;
+
+    Ok(())
+}
+
+/// Writes aggregated word count results in [word_counts] that pass [filter] to [out],
+/// followed by each song's own if [per_song] is set and the repeated phrases in
+/// [phrase_counts] if phrases were counted.
+pub fn write_word_counts_for_songs(
+    out: &mut dyn Write,
+    word_counts: Vec<SongWordCountsResult>,
+    phrase_counts: Option<Vec<SongWordCountsResult>>,
+    filter: &WordCountFilter,
+    per_song: bool) -> io::Result<()> {
+    // Record the total number of songs for later.
+    let num_songs = word_counts.len();
+
+    // Split [word_counts] into successes and failures.
+    // [successful_word_counts] contains the actual [WordCounts] and [failures]
+    // contains the [Result<_, _>].
+    let (successful_word_counts, failures) = word_counts
+        .into_iter()
+        .fold((vec![], vec![]), |mut acc, result| {
+            match result.1 {
+                Ok(counts) => {
+                    acc.0.push((result.0, counts));
+                },
+                _ => acc.1.push(result)
+            };
+
+            acc
+        });
+
+    let aggregated_word_counts = word_count::aggregate_word_counts(successful_word_counts.iter()
+        .map(|(_, counts)| counts.clone())
+        .collect());
+
+    // Collect some metrics for use in reporting failures.
+    let stats = RunStats::new(num_songs, failures.len());
+
+    writeln!(out, "Fetched {}/{} songs; success rate: {}%", stats.successes, stats.songs, stats.success_rate)?;
+    writeln!(out)?;
+
+    writeln!(out, "Failures:")?;
+    for failure in &failures {
+        writeln!(out, "\t{:?}", &failure.0)?;
+    }
+
+    writeln!(out, "-------")?;
+
+    writeln!(out, "Most Common Words:")?;
+    for kvp in filter.apply(&aggregated_word_counts) {
+        writeln!(out, "{}: {}", kvp.0, kvp.1)?;
+    }
+
+    if per_song {
+        writeln!(out, "-------")?;
+
+        writeln!(out, "Words per Song:")?;
+        for (song, counts) in &successful_word_counts {
+            writeln!(out, "{} - {}", song.artist, song.name)?;
+
+            for kvp in filter.apply(counts) {
+                writeln!(out, "\t{}: {}", kvp.0, kvp.1)?;
+            }
+        }
+    }
+
+    if let Some(phrase_counts) = phrase_counts {
+        let aggregated_phrase_counts = word_count::aggregate_word_counts(phrase_counts.into_iter()
+            .filter_map(|(_, counts)| counts.ok())
+            .collect());
+
+        writeln!(out, "-------")?;
+
+        // Phrases that only show up once aren't interesting and would drown out
+        // the ones that do repeat.
+        writeln!(out, "Most Common Phrases:")?;
+        for kvp in filter.apply(&aggregated_phrase_counts) {
+            if *kvp.1 > 1 {
+                writeln!(out, "{}: {}", kvp.0, kvp.1)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes [distinctive_words] to [out], ordered by group.
+pub fn write_distinctive_words(out: &mut dyn Write, group_by: GroupBy, distinctive_words: HashMap<String, Vec<(String, f64)>>) -> io::Result<()> {
+    let mut groups = distinctive_words.into_iter().collect::<Vec<_>>();
+    groups.sort_by(|a, b| a.0.cmp(&b.0));
+
+    writeln!(out, "-------")?;
+
+    writeln!(out, "Most Distinctive Words (by {}):", group_by.name())?;
+    for (group, words) in groups {
+        writeln!(out, "{}", group)?;
+
+        for (word, score) in words {
+            writeln!(out, "\t{}: {:.4}", word, score)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the [comparison] of the sets of songs named [names] to [out], followed by
+/// the most similar [pairs] of their songs.
+pub fn write_comparison(out: &mut dyn Write, names: (&str, &str), comparison: &VocabularyComparison, pairs: &[SongSimilarity]) -> io::Result<()> {
+    let (left, right) = names;
+
+    writeln!(out, "-------")?;
+
+    writeln!(out, "Comparison of {} and {}:", left, right)?;
+    writeln!(out, "\tJaccard similarity: {:.4}", comparison.jaccard_similarity)?;
+    writeln!(out, "\tCosine similarity: {:.4}", comparison.cosine_similarity)?;
+
+    writeln!(out, "Shared words ({}):", comparison.shared_word_count)?;
+    for (word, count) in &comparison.shared_words {
+        writeln!(out, "\t{}: {}", word, count)?;
+    }
+
+    writeln!(out, "Only in {} ({}):", left, comparison.left_only_word_count)?;
+    for (word, count) in &comparison.left_only_words {
+        writeln!(out, "\t{}: {}", word, count)?;
+    }
+
+    writeln!(out, "Only in {} ({}):", right, comparison.right_only_word_count)?;
+    for (word, count) in &comparison.right_only_words {
+        writeln!(out, "\t{}: {}", word, count)?;
+    }
+
+    writeln!(out, "Biggest frequency differences:")?;
+    for delta in &comparison.deltas {
+        writeln!(out, "\t{}: {:.2}% -> {:.2}% ({:+.2}%)", delta.word, delta.left * 100f64, delta.right * 100f64, delta.delta() * 100f64)?;
+    }
+
+    writeln!(out, "Most similar songs:")?;
+    for pair in pairs {
+        writeln!(out, "\t{} - {} ~ {} - {}: {:.4}", pair.left.artist, pair.left.name, pair.right.artist, pair.right.name, pair.similarity)?;
+    }
+
+    Ok(())
+}
+
+/// Writes the [LyricsAnalysis] of each of the successfully fetched [lyrics] and of
+/// all of them together to [out].
+pub fn write_analysis_for_songs(out: &mut dyn Write, lyrics: &[SongLyricsResult]) -> io::Result<()> {
+    let successful_lyrics = lyrics.iter()
+        .filter_map(|(song, lyrics)| lyrics.as_ref().ok().map(|lyrics| (*song, lyrics)))
+        .collect::<Vec<_>>();
+
+    writeln!(out, "-------")?;
+
+    writeln!(out, "Analysis:")?;
+    for (song, lyrics) in &successful_lyrics {
+        writeln!(out, "{} - {}", song.artist, song.name)?;
+        write_analysis(out, &analysis::analyze_lyrics(lyrics))?;
+    }
+
+    writeln!(out, "All songs")?;
+    write_analysis(out, &analysis::analyze_collection(&successful_lyrics.iter()
+        .map(|(_, lyrics)| lyrics.as_str())
+        .collect::<Vec<_>>()))?;
+
+    Ok(())
+}
+
+/// Writes the [SongSentiment] of each of the successfully fetched [lyrics] to [out],
+/// followed by the average sentiment of all of them.
+pub fn write_sentiment_for_songs(out: &mut dyn Write, lyrics: &[SongLyricsResult]) -> io::Result<()> {
+    let analyzer = SentimentAnalyzer::new();
+
+    let sentiments = lyrics.iter()
+        .filter_map(|(song, lyrics)| lyrics.as_ref().ok().map(|lyrics| (*song, analyzer.analyze(lyrics))))
+        .collect::<Vec<_>>();
+
+    writeln!(out, "-------")?;
+
+    writeln!(out, "Sentiment:")?;
+    for (song, sentiment) in &sentiments {
+        writeln!(out, "{} - {}", song.artist, song.name)?;
+        write_sentiment(out, &sentiment.overall)?;
+
+        for line in &sentiment.lines {
+            writeln!(out, "\t\t{:+.3}\t{}", line.sentiment.compound, line.line)?;
+        }
+    }
+
+    let average = match sentiments.len() {
+        0 => 0f64,
+        num_songs => sentiments.iter().map(|(_, sentiment)| sentiment.overall.compound).sum::<f64>() / num_songs as f64
+    };
+
+    writeln!(out, "Average sentiment: {:+.3}", average)?;
+
+    Ok(())
+}
+
+fn write_sentiment(out: &mut dyn Write, sentiment: &Sentiment) -> io::Result<()> {
+    let emotions = sentiment.dominant_emotions(3).iter()
+        .map(|(emotion, count)| format!("{} ({})", emotion.name(), count))
+        .collect::<Vec<_>>();
+
+    writeln!(out, "\tsentiment: {:+.3}", sentiment.compound)?;
+    writeln!(
+        out,
+        "\tpositive: {:.1}%, negative: {:.1}%, neutral: {:.1}%",
+        sentiment.positive * 100f64,
+        sentiment.negative * 100f64,
+        sentiment.neutral * 100f64)?;
+    writeln!(out, "\temotions: {}", emotions.join(", "))?;
+
+    Ok(())
+}
+
+/// Writes the [RhymeAnalysis] of each of the successfully fetched [lyrics] to [out],
+/// looking up how words sound with [pronouncer].
+pub fn write_rhymes_for_songs(out: &mut dyn Write, lyrics: &[SongLyricsResult], pronouncer: &Pronouncer) -> io::Result<()> {
+    writeln!(out, "-------")?;
+
+    writeln!(out, "Rhymes:")?;
+    for (song, lyrics) in lyrics {
+        if let Ok(lyrics) = lyrics {
+            let analysis = rhyme::analyze_rhymes(lyrics, pronouncer);
+
+            writeln!(out, "{} - {}", song.artist, song.name)?;
+            writeln!(out, "\taverage syllables per line: {:.2}", analysis.average_syllables())?;
+
+            for stanza in &analysis.stanzas {
+                writeln!(out, "\t{}", stanza.scheme)?;
+
+                for line in &stanza.lines {
+                    writeln!(out, "\t\t{} ({})\t{}", line.rhyme, line.syllables, line.line)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the language each of the successfully fetched [lyrics] is detected to be
+/// in to [out], grouped by language.
+pub fn write_languages_for_songs(out: &mut dyn Write, lyrics: &[SongLyricsResult]) -> io::Result<()> {
+    let languages = lyrics.iter()
+        .filter_map(|(song, lyrics)| lyrics.as_ref().ok().map(|lyrics| (*song, word_count::detect_language(lyrics))))
+        .fold(BTreeMap::new(), |mut acc: BTreeMap<String, Vec<_>>, (song, detected)| {
+            let name = detected.as_ref()
+                .map(|detected| detected.name.clone())
+                .unwrap_or_else(|| "Unknown".to_string());
+
+            acc.entry(name).or_default().push((song, detected));
+
+            acc
+        });
+
+    writeln!(out, "-------")?;
+
+    writeln!(out, "Languages:")?;
+    for (name, songs) in languages {
+        writeln!(out, "{} ({} songs)", name, songs.len())?;
+
+        for (song, detected) in songs {
+            match detected {
+                Some(detected) => writeln!(
+                    out,
+                    "\t{} - {} (confidence: {:.2}{})",
+                    song.artist,
+                    song.name,
+                    detected.confidence,
+                    if detected.reliable { "" } else { ", unreliable" })?,
+                None => writeln!(out, "\t{} - {}", song.artist, song.name)?
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the [Explicitness] of each of the successfully fetched [lyrics] to [out].
+pub fn write_explicitness_for_songs(out: &mut dyn Write, lyrics: &[SongLyricsResult], profanity_filter: &ProfanityFilter) -> io::Result<()> {
+    writeln!(out, "-------")?;
+
+    writeln!(out, "Explicitness:")?;
+    for (song, lyrics) in lyrics {
+        if let Ok(lyrics) = lyrics {
+            let explicitness = get_explicitness(lyrics, profanity_filter);
+
+            let words = word_count::sort_word_counts(&explicitness.counts, word_count::SortOrder::Descending)
+                .into_iter()
+                .map(|(word, count)| format!("{} ({})", profanity_filter.mask_word(word), count))
+                .collect::<Vec<_>>();
+
+            writeln!(
+                out,
+                "{} - {}: {}",
+                song.artist,
+                song.name,
+                if explicitness.explicit { "explicit" } else { "clean" })?;
+            writeln!(
+                out,
+                "\tprofane words: {} ({:.1} per 100 words)",
+                explicitness.profane_words,
+                explicitness.score)?;
+
+            if !words.is_empty() {
+                writeln!(out, "\t{}", words.join(", "))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn write_analysis(out: &mut dyn Write, analysis: &LyricsAnalysis) -> io::Result<()> {
+    writeln!(out, "\twords: {} ({} unique)", analysis.word_count, analysis.unique_word_count)?;
+    writeln!(out, "\ttype-token ratio: {:.3}", analysis.type_token_ratio)?;
+    writeln!(out, "\thapax legomena: {}", analysis.hapax_legomena)?;
+    writeln!(out, "\taverage line length: {:.2} words", analysis.average_line_length)?;
+    writeln!(out, "\taverage word length: {:.2} characters", analysis.average_word_length)?;
+    writeln!(out, "\trepeated lines: {:.1}%", analysis.repetition_ratio * 100f64)?;
+    writeln!(out, "\tchorus repeats: {}", analysis.chorus_repeats)?;
+    writeln!(out, "\tcompression ratio: {:.3}", analysis.compression_ratio)?;
+
+    Ok(())
+}
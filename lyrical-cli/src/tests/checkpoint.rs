@@ -0,0 +1,102 @@
+use std::cell::Cell;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use liblyrical::lyrics::{DecoratingLyricsFetcher, FetchError, LyricsFetcher};
+
+use crate::checkpoint::*;
+use crate::progress::*;
+use super::*;
+
+fn checkpoint_path(name: &str) -> PathBuf {
+    std::env::temp_dir()
+        .join(format!("lyrical-cli-checkpoint-{}", std::process::id()))
+        .join(name)
+}
+
+fn checkpointed_fetcher(path: &PathBuf, resume: bool, fetches: &Rc<Cell<usize>>) -> impl LyricsFetcher {
+    DecoratingLyricsFetcher::new(
+        MockLyricsFetcher { fetches: fetches.clone(), ..Default::default() },
+        vec![Box::new(CheckpointMiddleware::open(path, resume).unwrap())])
+}
+
+#[test]
+fn checkpoint_lets_runs_resume() {
+    let path = checkpoint_path("resume.ndjson");
+    let songs = [song("House of Fire", "Dave Rodgers"), song("Unknown", ""), song("Night of Fire", "Niko")];
+    let fetches = Rc::new(Cell::new(0));
+
+    let mut interrupted = checkpointed_fetcher(&path, false, &fetches);
+    interrupted.fetch_lyrics(&songs[0]).unwrap();
+    interrupted.fetch_lyrics(&songs[1]).unwrap_err();
+
+    let mut resumed = checkpointed_fetcher(&path, true, &fetches);
+    let results = songs.iter().map(|song| resumed.fetch_lyrics(song)).collect::<Vec<_>>();
+
+    assert_eq!(results, vec![Ok("House of Fire lyrics\n".to_string()), Err(FetchError::not_found("No results")), Ok("Night of Fire lyrics\n".to_string())]);
+    // Only the song that failed and the one that wasn't fetched yet are fetched again.
+    assert_eq!(fetches.get(), 4);
+    assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 2);
+
+    remove_checkpoint(&path).unwrap();
+    assert!(!path.exists());
+}
+
+#[test]
+fn checkpoint_starts_over_unless_resuming() {
+    let path = checkpoint_path("start-over.ndjson");
+    let fetches = Rc::new(Cell::new(0));
+
+    checkpointed_fetcher(&path, false, &fetches).fetch_lyrics(&song("House of Fire", "Dave Rodgers")).unwrap();
+    checkpointed_fetcher(&path, false, &fetches).fetch_lyrics(&song("House of Fire", "Dave Rodgers")).unwrap();
+
+    assert_eq!(fetches.get(), 2);
+    assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 1);
+
+    remove_checkpoint(&path).unwrap();
+}
+
+#[test]
+fn checkpoint_skips_lines_cut_short() {
+    let path = checkpoint_path("cut-short.ndjson");
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, "{\"song\":{\"name\":\"House of Fire\",\"artist\":\"Dave Rodgers\",\"uri\":null},\"lyrics\":\"Fire\"}\n{\"song\":{\"na").unwrap();
+
+    let fetches = Rc::new(Cell::new(0));
+    let mut fetcher = checkpointed_fetcher(&path, true, &fetches);
+
+    assert_eq!(fetcher.fetch_lyrics(&song("House of Fire", "Dave Rodgers")), Ok("Fire".to_string()));
+    assert_eq!(fetches.get(), 0);
+
+    remove_checkpoint(&path).unwrap();
+}
+
+#[test]
+fn checkpoint_records_songs_after_a_line_cut_short() {
+    let path = checkpoint_path("after-cut-short.ndjson");
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, "{\"song\":{\"na").unwrap();
+
+    let fetches = Rc::new(Cell::new(0));
+    checkpointed_fetcher(&path, true, &fetches).fetch_lyrics(&song("Night of Fire", "Niko")).unwrap();
+    checkpointed_fetcher(&path, true, &fetches).fetch_lyrics(&song("Night of Fire", "Niko")).unwrap();
+
+    // The second run finds the song recorded by the first instead of fetching it again.
+    assert_eq!(fetches.get(), 1);
+    assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 2);
+
+    remove_checkpoint(&path).unwrap();
+}
+
+#[test]
+fn progress_moves_along_with_each_fetch() {
+    let bar = make_progress_bar(false);
+    set_progress_length(&bar, 2);
+
+    let mut fetcher = DecoratingLyricsFetcher::new(MockLyricsFetcher::default(), vec![Box::new(ProgressMiddleware::new(bar.clone()))]);
+    fetcher.fetch_lyrics(&song("House of Fire", "Dave Rodgers")).unwrap();
+    fetcher.fetch_lyrics(&song("Unknown", "")).unwrap_err();
+
+    assert_eq!(bar.position(), 2);
+}
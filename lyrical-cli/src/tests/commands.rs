@@ -0,0 +1,208 @@
+use std::fs;
+
+use clap::App;
+
+use liblyrical::lyrics::{CacheEntries, CacheEntry, FetchError, LyricsFetcher, LyricsFetcherConfigBuilder};
+use liblyrical::word_count::WordCountOptions;
+
+use crate::checkpoint::CHECKPOINT_LOCATION;
+use crate::commands::*;
+use crate::outcome::{CommandError, RunSummary};
+use super::*;
+
+fn parse(args: &[&str]) -> clap::ArgMatches<'static> {
+    App::new("Lyrical")
+        .subcommands(subcommands())
+        .get_matches_from_safe(args)
+        .unwrap()
+}
+
+#[test]
+fn subcommands_share_the_fetcher_config() {
+    for command in &["fetch", "count", "analyze"] {
+        let matches = parse(&["lyrical", command, "--cache-file", "songs.json", "--proxy", "direct", "--proxy", "https://localhost:8080", "--no-retry-failures"]);
+        let (_, matches) = matches.subcommand();
+
+        let expected = LyricsFetcherConfigBuilder::default()
+            .cache_path("songs.json")
+            .proxies(vec![None, Some("https://localhost:8080".to_string())])
+            .retry_cached_failures(false)
+            .build()
+            .unwrap();
+
+        assert_eq!(get_fetcher_config(matches.unwrap()), Ok(expected));
+    }
+}
+
+#[test]
+fn cache_file_can_come_before_or_after_cache_commands() {
+    for args in &[["lyrical", "cache", "--cache-file", "songs.json", "stats"], ["lyrical", "cache", "stats", "--cache-file", "songs.json"]] {
+        let matches = parse(args);
+        let (_, cache) = matches.subcommand();
+
+        assert_eq!(get_cache_path(cache.unwrap()), "songs.json");
+    }
+}
+
+#[test]
+fn count_rejects_text_only_options_for_other_outputs() {
+    for (option, value) in &[("--ngrams", Some("2")), ("--per-song", None)] {
+        let mut args = vec!["lyrical", "count", "-j", "[]", "--output", "ndjson", option];
+        args.extend(value);
+
+        let matches = parse(&args);
+        let (_, matches) = matches.subcommand();
+
+        assert_eq!(run_count(matches.unwrap()), Err(CommandError::Usage(format!("{} only applies to --output text", option))));
+    }
+}
+
+#[test]
+fn runs_are_only_checkpointed_when_asked() {
+    let checkpoint_path = |args: &[&str]| {
+        let matches = parse(args);
+
+        get_checkpoint_path(matches.subcommand().1.unwrap()).map(str::to_string)
+    };
+
+    assert_eq!(checkpoint_path(&["lyrical", "fetch", "-j", "[]"]), None);
+    assert_eq!(checkpoint_path(&["lyrical", "fetch", "-j", "[]", "--checkpoint"]), Some(CHECKPOINT_LOCATION.to_string()));
+    assert_eq!(checkpoint_path(&["lyrical", "fetch", "--checkpoint", "run.ndjson", "-j", "[]"]), Some("run.ndjson".to_string()));
+    assert_eq!(checkpoint_path(&["lyrical", "count", "-j", "[]", "--resume"]), Some(CHECKPOINT_LOCATION.to_string()));
+}
+
+#[test]
+fn finish_run_writes_failure_report_even_if_fetching_failed() {
+    let path = std::env::temp_dir().join(format!("lyrical-cli-failure-report-{}.json", std::process::id()));
+    let matches = parse(&["lyrical", "fetch", "-j", "[]", "--failure-report", path.to_str().unwrap()]);
+    let (_, matches) = matches.subcommand();
+
+    let summary = RunSummary { songs: 2, failures: vec![(song("Let Me Go", "Mr. Foo"), FetchError::not_found("No results"))] };
+    let fetched = Err(CommandError::Runtime("Failed to remove checkpoint".to_string()));
+
+    assert_eq!(finish_run(matches.unwrap(), &summary, fetched.clone(), Ok(())), fetched.map(|_| 0));
+    assert!(fs::read_to_string(&path).unwrap().contains("\"Let Me Go\""));
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn compare_needs_two_sets_of_songs() {
+    let matches = parse(&["lyrical", "compare", "--left", "a.json", "--right", "b.json", "--cache-file", "songs.json"]);
+    let (_, matches) = matches.subcommand();
+
+    assert_eq!(get_fetcher_config(matches.unwrap()).unwrap().cache_path(), "songs.json");
+    assert!(App::new("Lyrical").subcommands(subcommands()).get_matches_from_safe(["lyrical", "compare", "--left", "a.json"]).is_err());
+    assert!(App::new("Lyrical").subcommands(subcommands()).get_matches_from_safe(["lyrical", "compare", "--artists", "Manuel"]).is_err());
+}
+
+#[test]
+fn can_pick_songs_by_artist() {
+    let songs = vec![
+        song("House of Fire", "Dave Rodgers"),
+        song("Deja Vu", "Dave Rodgers feat. Ken Blast"),
+        song("Gas Gas Gas", "Manuel"),
+    ];
+
+    assert_eq!(songs_by_artist(&songs, "dave rodgers"), songs[..2].to_vec());
+    assert_eq!(songs_by_artist(&songs, "Niko"), vec![]);
+}
+
+#[test]
+fn can_count_text_files() {
+    let dir = std::env::temp_dir().join(format!("lyrical-cli-text-files-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let named = dir.join("Dave Rodgers - House of Fire.txt");
+    let unnamed = dir.join("untitled.txt");
+    fs::write(&named, "House of fire").unwrap();
+    fs::write(&unnamed, "No name").unwrap();
+
+    let mut fetcher = TextFileLyricsFetcher::new([&named, &unnamed]);
+    let songs = fetcher.songs().collect::<Result<Vec<_>, _>>().unwrap();
+
+    assert_eq!(songs, vec![song("House of Fire", "Dave Rodgers"), song("untitled", "")]);
+    assert_eq!(fetcher.fetch_lyrics(&songs[0]), Ok("House of fire".to_string()));
+    assert_eq!(fetcher.fetch_lyrics(&songs[1]), Ok("No name".to_string()));
+    assert!(fetcher.fetch_lyrics(&song("Night of Fire", "Niko")).is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn can_fetch_songs() {
+    let songs: crate::input::Songs = Box::new(vec![
+        Ok(song("House of Fire", "Dave Rodgers")),
+        Ok(song("Unknown", "")),
+        Err("Line 3: Expected \"Artist - Title\"".to_string()),
+    ].into_iter());
+
+    let mut out = vec![];
+    fetch_songs(&mut out, &mut MockLyricsFetcher::default(), songs, None, &mut RunSummary::default()).unwrap();
+
+    assert_eq!(String::from_utf8(out).unwrap(), "Dave Rodgers - House of Fire\n\nHouse of Fire lyrics\n\n");
+}
+
+#[test]
+fn can_save_fetched_songs() {
+    let dir = std::env::temp_dir().join(format!("lyrical-cli-fetch-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let songs: crate::input::Songs = Box::new(vec![Ok(song("T.N.T.", "AC/DC"))].into_iter());
+
+    let mut out = vec![];
+    fetch_songs(&mut out, &mut MockLyricsFetcher::default(), songs, Some(&dir), &mut RunSummary::default()).unwrap();
+
+    let path = dir.join("AC_DC - T.N.T..txt");
+    assert_eq!(String::from_utf8(out).unwrap(), format!("{}\n", path.display()));
+    assert_eq!(fs::read_to_string(&path).unwrap(), "T.N.T. lyrics\n");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn can_report_cache_contents() {
+    let mut cache = CacheEntries::new();
+    cache.insert(&song("House of Fire", "Dave Rodgers"), CacheEntry::Success("House of fire".to_string()));
+    cache.insert(&song("Night of Fire", "Niko"), CacheEntry::Failure(FetchError::not_found("No results")));
+    cache.insert(&song("Night of Fire (Extended Mix)", "Niko"), CacheEntry::Alias(song("Night of Fire", "Niko")));
+
+    let mut stats = vec![];
+    write_cache_stats(&mut stats, &cache).unwrap();
+
+    assert_eq!(String::from_utf8(stats).unwrap(), "Songs: 3\nLyrics: 1\nFailures: 1\nAliases: 1\n");
+
+    let mut failures = vec![];
+    write_cache_entries(&mut failures, &cache, true).unwrap();
+
+    assert_eq!(String::from_utf8(failures).unwrap(), "niko - night of fire: failed (No results)\n");
+}
+
+#[test]
+fn serve_fetches_and_counts_songs() {
+    let body = r#"[{"name": "House of Fire", "artist": "Dave Rodgers"}, {"name": "Unknown", "artist": ""}]"#;
+
+    let fetched = respond("POST", "/fetch", body, &mut MockLyricsFetcher::default(), &WordCountOptions::default());
+    let json = serde_json::from_str::<serde_json::Value>(&fetched.body).unwrap();
+
+    assert_eq!(fetched.status, 200);
+    assert_eq!(json[0]["lyrics"], "House of Fire lyrics\n");
+    assert_eq!(json[1]["error"], serde_json::json!({ "category": "not-found", "message": "No results" }));
+
+    let counted = respond("POST", "/count?pretty", body, &mut MockLyricsFetcher::default(), &WordCountOptions::default());
+    let json = serde_json::from_str::<serde_json::Value>(&counted.body).unwrap();
+
+    assert_eq!(counted.status, 200);
+    assert_eq!(json["stats"]["failures"], 1);
+    assert_eq!(json["aggregated"][0], serde_json::json!({ "word": "fire", "count": 1 }));
+}
+
+#[test]
+fn serve_rejects_bad_requests() {
+    let respond = |method, url, body| respond(method, url, body, &mut MockLyricsFetcher::default(), &WordCountOptions::default()).status;
+
+    assert_eq!(respond("POST", "/count", "not json"), 400);
+    assert_eq!(respond("GET", "/fetch", ""), 405);
+    assert_eq!(respond("GET", "/lyrics", ""), 404);
+    assert_eq!(respond("GET", "/metrics", ""), 200);
+}
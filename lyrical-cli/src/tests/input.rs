@@ -0,0 +1,154 @@
+use std::fs::File;
+use std::io::Cursor;
+use std::path::Path;
+
+use id3::{Tag, TagLike, Version};
+
+use liblyrical::lyrics::{SongDescriptor, SongUri};
+
+use crate::input::*;
+use super::song;
+
+#[test]
+fn can_read_spotify_account_exports() {
+    let songs = parse_songs(include_str!("../../test_data/exports/spotify-playlist.json"), InputFormat::Spotify, Path::new("."));
+
+    assert_eq!(songs, Ok(vec![
+        SongDescriptor { uri: Some(SongUri::SpotifyUri("spotify:track:4a1DdOB3iRdiK4K3I8tFiE".to_string())), ..song("Deja Vu", "Dave Rodgers") },
+        SongDescriptor { uri: Some(SongUri::SpotifyUri("spotify:track:6R2ZVhHFvrDK4PWzeTaaGj".to_string())), ..song("Night of Fire", "Niko") },
+    ]));
+}
+
+#[test]
+fn can_read_spotify_web_api_playlists() {
+    let playlist = r#"{
+        "name": "Eurobeat",
+        "tracks": {
+            "items": [
+                { "track": { "name": "Deja Vu", "artists": [{ "name": "Dave Rodgers" }], "uri": "spotify:track:4a1DdOB3iRdiK4K3I8tFiE" } },
+                { "track": { "name": "Get Lucky", "artists": [{ "name": "Daft Punk" }, { "name": "Pharrell Williams" }] } },
+                { "track": null }
+            ]
+        }
+    }"#;
+
+    assert_eq!(
+        parse_songs(playlist, InputFormat::Spotify, Path::new(".")),
+        Ok(vec![
+            SongDescriptor { uri: Some(SongUri::SpotifyUri("spotify:track:4a1DdOB3iRdiK4K3I8tFiE".to_string())), ..song("Deja Vu", "Dave Rodgers") },
+            song("Get Lucky", "Daft Punk, Pharrell Williams"),
+        ]));
+}
+
+#[test]
+fn spotify_export_errors_say_what_is_wrong() {
+    let error = parse_songs(r#"{ "playlists": [{ "items": [{ "track": { "trackName": "Deja Vu" } }] }] }"#, InputFormat::Spotify, Path::new("."))
+        .unwrap_err();
+
+    assert!(error.contains("missing field `artistName`"), "{}", error);
+    assert!(parse_songs(r#"{ "name": "Eurobeat" }"#, InputFormat::Spotify, Path::new(".")).unwrap_err().contains("expected an account data export"));
+}
+
+#[test]
+fn can_read_exportify_csv() {
+    let songs = parse_songs(include_str!("../../test_data/exports/exportify.csv"), InputFormat::Exportify, Path::new("."));
+
+    assert_eq!(songs, Ok(vec![
+        song("Deja Vu", "Dave Rodgers"),
+        song("Running in the 90s", "Max Coveri, Dave Rodgers"),
+        song("Yonkers", "Tyler, The Creator"),
+    ]));
+}
+
+#[test]
+fn exportify_csv_needs_track_and_artist_columns() {
+    assert!(parse_songs("Track URI,Album Name\nspotify:track:1,Foo\n", InputFormat::Exportify, Path::new(".")).is_err());
+}
+
+#[test]
+fn can_read_itunes_libraries() {
+    let songs = parse_songs(include_str!("../../test_data/exports/itunes-library.xml"), InputFormat::Itunes, Path::new("."));
+
+    assert_eq!(songs, Ok(vec![song("Deja Vu", "Dave Rodgers"), song("Rock & Roll", "Led Zeppelin")]));
+}
+
+#[test]
+fn can_read_m3u_playlists() {
+    let songs = parse_songs(include_str!("../../test_data/playlists/eurobeat.m3u"), InputFormat::M3u, Path::new("."));
+
+    assert_eq!(songs, Ok(vec![
+        song("Deja Vu", "Dave Rodgers"),
+        song("I Won't Fall Apart - Eurobeat Version", "Jager"),
+        song("Night of Fire", "Niko"),
+    ]));
+}
+
+#[test]
+fn can_read_pls_playlists() {
+    let songs = parse_songs(include_str!("../../test_data/playlists/eurobeat.pls"), InputFormat::Pls, Path::new("."));
+
+    assert_eq!(songs, Ok(vec![song("Deja Vu", "Dave Rodgers"), song("Night of Fire", "Niko")]));
+}
+
+#[test]
+fn can_read_xspf_playlists() {
+    let songs = parse_songs(include_str!("../../test_data/playlists/eurobeat.xspf"), InputFormat::Xspf, Path::new("."));
+
+    assert_eq!(songs, Ok(vec![song("Deja Vu", "Dave Rodgers"), song("Night of Fire", "Niko")]));
+}
+
+#[test]
+fn playlists_fall_back_to_audio_tags() {
+    let dir = std::env::temp_dir().join(format!("lyrical-cli-tags-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    File::create(dir.join("track01.mp3")).unwrap();
+
+    let mut tag = Tag::new();
+    tag.set_artist("Dave Rodgers");
+    tag.set_title("Deja Vu");
+    tag.write_to_path(dir.join("track01.mp3"), Version::Id3v24).unwrap();
+
+    let songs = parse_songs("#EXTM3U\ntrack01.mp3\ntrack02.mp3\n", InputFormat::M3u, &dir);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(songs, Ok(vec![song("Deja Vu", "Dave Rodgers")]));
+}
+
+#[test]
+fn can_read_ndjson_and_text_lines() {
+    let ndjson = "{\"name\": \"Deja Vu\", \"artist\": \"Dave Rodgers\"}\n\n{\"name\": \"Night of Fire\"}\n";
+    let songs = read_songs(Cursor::new(ndjson), InputFormat::Ndjson, Path::new(".")).unwrap().collect::<Vec<_>>();
+
+    assert_eq!(songs.len(), 2);
+    assert_eq!(songs[0], Ok(song("Deja Vu", "Dave Rodgers")));
+    assert!(songs[1].as_ref().unwrap_err().starts_with("Line 3:"));
+
+    let text = "# Eurobeat\nDave Rodgers - Deja Vu\nJager - I Won't Fall Apart - Eurobeat Version\nNight of Fire\n";
+    let songs = read_songs(Cursor::new(text), InputFormat::Text, Path::new(".")).unwrap().collect::<Vec<_>>();
+
+    assert_eq!(songs[..2], [Ok(song("Deja Vu", "Dave Rodgers")), Ok(song("I Won't Fall Apart - Eurobeat Version", "Jager"))]);
+    assert!(songs[2].is_err());
+}
+
+#[test]
+fn read_songs_reads_line_based_input_lazily() {
+    // Reading the first song mustn't wait for (or fail on) the rest of the input.
+    let input = Cursor::new(b"Dave Rodgers - Deja Vu\n\xff\xfe\n".to_vec());
+    let mut songs = read_songs(input, InputFormat::Text, Path::new(".")).unwrap();
+
+    assert_eq!(songs.next(), Some(Ok(song("Deja Vu", "Dave Rodgers"))));
+    assert!(songs.next().unwrap().is_err());
+    assert_eq!(songs.next(), None);
+}
+
+#[test]
+fn read_songs_reports_invalid_documents() {
+    assert!(read_songs(Cursor::new("not json"), InputFormat::Json, Path::new(".")).is_err());
+}
+
+#[test]
+fn can_parse_input_formats() {
+    assert_eq!("Exportify".parse::<InputFormat>(), Ok(InputFormat::Exportify));
+    assert_eq!("m3u8".parse::<InputFormat>(), Ok(InputFormat::M3u));
+    assert!("wpl".parse::<InputFormat>().is_err());
+}
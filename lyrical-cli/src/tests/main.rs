@@ -1,20 +1,17 @@
-use crate::*;
-use liblyrical::utils::{stringify_map_keys};
-
-#[derive(Debug)]
-struct MockLyricsFetcher {
-    pub lyrics: String,
-}
+use std::io;
+use std::io::Write;
 
-impl lyrics::LyricsFetcher for MockLyricsFetcher {
-    fn fetch_lyrics(&mut self, _song: &lyrics::SongDescriptor) -> Result<String, String> {
-        Ok(self.lyrics.clone())
-    }
-}
+use crate::commands::*;
+use crate::input;
+use super::MockLyricsFetcher;
+use liblyrical::analysis::ProfanityFilter;
+use liblyrical::lyrics;
+use liblyrical::utils::{stringify_map_keys};
+use liblyrical::word_count::{Language, StopWords, WordCountFilter, WordCountFilterBuilder, WordCountOptions, WordCountOptionsBuilder};
 
 #[test]
 fn can_get_word_count_for_song() {
-    let mut fetcher = MockLyricsFetcher { lyrics: include_str!("../../test_data/songs/house_of_fire.txt").to_string() };
+    let mut fetcher = MockLyricsFetcher::with_lyrics(include_str!("../../test_data/songs/house_of_fire.txt"));
 
     let song = lyrics::SongDescriptor {
         name: "House of Fire".to_string(),
@@ -23,7 +20,7 @@ fn can_get_word_count_for_song() {
     };
 
     let songs = vec![song];
-    let counts = get_word_counts_for_songs(&mut fetcher, &songs);
+    let counts = count_words_for_lyrics(&fetch_lyrics_for_songs(&mut fetcher, &songs), &WordCountOptions::default());
 
     assert_eq!(counts.get(0).unwrap().1, Ok(stringify_map_keys(&hashmap!{
         "'cause" => 2,
@@ -89,4 +86,157 @@ fn can_get_word_count_for_song() {
         "you" => 6,
         "your" => 4,
     })));
-}
\ No newline at end of file
+}
+
+#[test]
+fn can_filter_stop_words_from_word_counts() {
+    let mut fetcher = MockLyricsFetcher::with_lyrics("Welcome to the house of fire");
+
+    let song = lyrics::SongDescriptor {
+        name: "House of Fire".to_string(),
+        artist: "Dave Rodgers".to_string(),
+        uri: None
+    };
+
+    let options = WordCountOptionsBuilder::default()
+        .stop_words(Some(StopWords::for_language(Language::English)))
+        .build()
+        .unwrap();
+
+    let songs = vec![song];
+    let counts = count_words_for_lyrics(&fetch_lyrics_for_songs(&mut fetcher, &songs), &options);
+
+    assert_eq!(counts.get(0).unwrap().1, Ok(stringify_map_keys(&hashmap!{
+        "welcome" => 1,
+        "house" => 1,
+        "fire" => 1,
+    })));
+}
+
+#[test]
+fn can_group_word_forms_in_word_counts() {
+    let mut fetcher = MockLyricsFetcher::with_lyrics("I'm dancing, she dances, we dance");
+
+    let song = lyrics::SongDescriptor {
+        name: "Dance".to_string(),
+        artist: "Mr. Foo".to_string(),
+        uri: None
+    };
+
+    let options = WordCountOptionsBuilder::default()
+        .expand_contractions(true)
+        .lemmatize(true)
+        .stemming(Some(Language::English))
+        .build()
+        .unwrap();
+
+    let songs = vec![song];
+    let counts = count_words_for_lyrics(&fetch_lyrics_for_songs(&mut fetcher, &songs), &options);
+
+    assert_eq!(counts.get(0).unwrap().1, Ok(stringify_map_keys(&hashmap!{
+        "i" => 1,
+        "be" => 1,
+        "dance" => 3,
+        "she" => 1,
+        "we" => 1,
+    })));
+}
+
+#[test]
+fn can_count_phrases_for_songs() {
+    let mut fetcher = MockLyricsFetcher::with_lyrics("Let me go, let me go\nLet me go");
+
+    let song = lyrics::SongDescriptor {
+        name: "House of Fire".to_string(),
+        artist: "Dave Rodgers".to_string(),
+        uri: None
+    };
+
+    let songs = vec![song];
+    let counts = count_phrases_for_lyrics(&fetch_lyrics_for_songs(&mut fetcher, &songs), &[3], &WordCountOptions::default());
+
+    assert_eq!(counts.get(0).unwrap().1, Ok(stringify_map_keys(&hashmap!{
+        "let me go" => 3,
+        "me go let" => 1,
+        "go let me" => 1,
+    })));
+}
+
+#[test]
+fn can_exclude_explicit_songs() {
+    let songs = [
+        lyrics::SongDescriptor { name: "Clean".to_string(), artist: "Mr. Foo".to_string(), uri: None },
+        lyrics::SongDescriptor { name: "Explicit".to_string(), artist: "Mr. Foo".to_string(), uri: None },
+    ];
+
+    let lyrics = vec![
+        (&songs[0], Ok("Welcome to the house of fire".to_string())),
+        (&songs[1], Ok("Holy shit, the house is on fire".to_string())),
+    ];
+
+    let (kept, excluded) = exclude_explicit_songs(lyrics, &ProfanityFilter::new());
+
+    assert_eq!(kept.iter().map(|(song, _)| song.name.as_str()).collect::<Vec<_>>(), vec!["Clean"]);
+    assert_eq!(excluded, vec![&songs[1]]);
+}
+
+#[test]
+fn can_stream_word_counts() {
+    let mut fetcher = MockLyricsFetcher::with_lyrics("Let me go, let me go, damn");
+    let songs: input::Songs = Box::new(vec![
+        Ok(lyrics::SongDescriptor { name: "Let Me Go".to_string(), artist: "Mr. Foo".to_string(), uri: None }),
+        Err("Line 2: Expected \"Artist - Title\"".to_string()),
+    ].into_iter());
+
+    let filter = WordCountFilterBuilder::default().top(2).build().unwrap();
+    let profanity_filter = ProfanityFilter::new();
+
+    let mut out = vec![];
+    stream_word_counts(&mut out, &mut fetcher, songs, &WordCountOptions::default(), &filter, None, Some(&profanity_filter)).1.unwrap();
+
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "{\"song\":{\"name\":\"Let Me Go\",\"artist\":\"Mr. Foo\",\"uri\":null},\"status\":\"ok\",\"word_counts\":[{\"word\":\"go\",\"count\":2},{\"word\":\"let\",\"count\":2}]}\n\
+         {\"status\":\"invalid\",\"reason\":\"Line 2: Expected \\\"Artist - Title\\\"\"}\n");
+}
+
+#[test]
+fn can_stream_excluded_songs() {
+    let mut fetcher = MockLyricsFetcher::with_lyrics("Holy shit");
+    let songs: input::Songs = Box::new(vec![
+        Ok(lyrics::SongDescriptor { name: "Explicit".to_string(), artist: "Mr. Foo".to_string(), uri: None }),
+    ].into_iter());
+
+    let profanity_filter = ProfanityFilter::new();
+
+    let mut out = vec![];
+    stream_word_counts(&mut out, &mut fetcher, songs, &WordCountOptions::default(), &WordCountFilter::default(), Some(&profanity_filter), None).1.unwrap();
+
+    assert!(String::from_utf8(out).unwrap().contains("\"status\":\"excluded\""));
+}
+
+#[test]
+fn streaming_keeps_summary_when_writing_fails() {
+    struct BrokenPipe;
+
+    impl Write for BrokenPipe {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::BrokenPipe, "broken pipe"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut fetcher = MockLyricsFetcher::with_lyrics("Let me go");
+    let songs: input::Songs = Box::new(vec![
+        Ok(lyrics::SongDescriptor { name: "Let Me Go".to_string(), artist: "Mr. Foo".to_string(), uri: None }),
+        Ok(lyrics::SongDescriptor { name: "Never Fetched".to_string(), artist: "Mr. Foo".to_string(), uri: None }),
+    ].into_iter());
+
+    let (summary, written) = stream_word_counts(&mut BrokenPipe, &mut fetcher, songs, &WordCountOptions::default(), &WordCountFilter::default(), None, None);
+
+    assert!(written.is_err());
+    assert_eq!(summary.songs, 1);
+}
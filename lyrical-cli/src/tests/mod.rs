@@ -1 +1,42 @@
-mod main;
\ No newline at end of file
+mod checkpoint;
+mod commands;
+mod input;
+mod main;
+mod outcome;
+mod report;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use liblyrical::lyrics::{FetchError, LyricsFetcher, SongDescriptor};
+
+/// A [LyricsFetcher] that answers every song with [lyrics] (or, if that isn't set,
+/// "<name> lyrics") except songs without an artist, which it can't find, counting
+/// how many songs it was asked for in [fetches].
+#[derive(Debug, Default)]
+pub struct MockLyricsFetcher {
+    pub lyrics: Option<String>,
+    pub fetches: Rc<Cell<usize>>,
+}
+
+impl MockLyricsFetcher {
+    pub fn with_lyrics(lyrics: &str) -> Self {
+        MockLyricsFetcher { lyrics: Some(lyrics.to_string()), ..Default::default() }
+    }
+}
+
+impl LyricsFetcher for MockLyricsFetcher {
+    fn fetch_lyrics(&mut self, song: &SongDescriptor) -> Result<String, FetchError> {
+        self.fetches.set(self.fetches.get() + 1);
+
+        match (song.artist.is_empty(), &self.lyrics) {
+            (true, _) => Err(FetchError::not_found("No results")),
+            (false, Some(lyrics)) => Ok(lyrics.clone()),
+            (false, None) => Ok(format!("{} lyrics\n", song.name))
+        }
+    }
+}
+
+pub fn song(name: &str, artist: &str) -> SongDescriptor {
+    SongDescriptor { name: name.to_string(), artist: artist.to_string(), uri: None }
+}
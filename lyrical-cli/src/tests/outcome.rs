@@ -0,0 +1,61 @@
+use liblyrical::lyrics::{FetchError, SongDescriptor};
+
+use crate::interrupt;
+use crate::outcome::*;
+use crate::report::FailureReport;
+use super::song;
+
+fn make_summary(songs: usize, failures: usize) -> RunSummary {
+    let song = SongDescriptor { name: "Let Me Go".to_string(), artist: "Mr. Foo".to_string(), uri: None };
+
+    RunSummary { songs, failures: vec![(song, FetchError::not_found("Not found")); failures] }
+}
+
+#[test]
+fn can_summarize_lyrics() {
+    let songs = [
+        SongDescriptor { name: "House of Fire".to_string(), artist: "Dave Rodgers".to_string(), uri: None },
+        SongDescriptor { name: "Let Me Go".to_string(), artist: "Mr. Foo".to_string(), uri: None },
+    ];
+    let lyrics = vec![(&songs[0], Ok("fire".to_string())), (&songs[1], Err(FetchError::not_found("Not found")))];
+
+    let summary = RunSummary::from_lyrics(&lyrics);
+
+    assert_eq!(summary.songs, 2);
+    assert_eq!(summary.failures, vec![(songs[1].clone(), FetchError::not_found("Not found"))]);
+    assert_eq!(summary.stats().success_rate, 50f64);
+}
+
+#[test]
+fn skipped_songs_count_towards_the_totals() {
+    let songs = [song("House of Fire", "Dave Rodgers"), song("Let Me Go", "Mr. Foo")];
+    let lyrics = vec![(&songs[0], Ok("fire".to_string())), (&songs[1], Err(interrupt::skipped()))];
+
+    let summary = RunSummary::from_lyrics(&lyrics);
+    let report = FailureReport::new(summary.stats(), &summary.failures);
+
+    assert_eq!(summary.stats().songs, 2);
+    assert_eq!(report.categories, btreemap!{ "skipped" => 1 });
+}
+
+#[test]
+fn exit_code_depends_on_failures() {
+    assert_eq!(make_summary(0, 0).exit_code(None), EXIT_SUCCESS);
+    assert_eq!(make_summary(4, 0).exit_code(None), EXIT_SUCCESS);
+    assert_eq!(make_summary(4, 1).exit_code(None), EXIT_PARTIAL_FAILURE);
+    assert_eq!(make_summary(4, 4).exit_code(None), EXIT_TOTAL_FAILURE);
+}
+
+#[test]
+fn exit_code_respects_fail_under() {
+    assert_eq!(make_summary(4, 1).exit_code(Some(75f64)), EXIT_SUCCESS);
+    assert_eq!(make_summary(4, 1).exit_code(Some(80f64)), EXIT_PARTIAL_FAILURE);
+    assert_eq!(make_summary(4, 4).exit_code(Some(0f64)), EXIT_TOTAL_FAILURE);
+}
+
+#[test]
+fn only_usage_errors_exit_with_usage_code() {
+    assert_eq!(CommandError::from("Invalid number".to_string()), CommandError::Usage("Invalid number".to_string()));
+    assert_eq!(CommandError::Usage("Invalid number".to_string()).exit_code(), EXIT_USAGE);
+    assert_eq!(CommandError::Runtime("Failed to write cache".to_string()).exit_code(), EXIT_ERROR);
+}
@@ -0,0 +1,143 @@
+use crate::commands::*;
+use crate::report::*;
+use liblyrical::lyrics;
+use liblyrical::lyrics::{FetchError, SongDescriptor};
+use liblyrical::utils::{stringify_map_keys};
+use liblyrical::word_count::{SortOrder, WordCountFilter, WordCountFilterBuilder};
+
+fn make_word_counts(songs: &[SongDescriptor]) -> Vec<SongWordCountsResult<'_>> {
+    vec![
+        (&songs[0], Ok(stringify_map_keys(&hashmap!{ "fire" => 3, "house" => 1 }))),
+        (&songs[1], Ok(stringify_map_keys(&hashmap!{ "fire" => 1, "go" => 2 }))),
+        (&songs[2], Err("Not found".to_string())),
+    ]
+}
+
+fn make_songs() -> Vec<SongDescriptor> {
+    vec![
+        lyrics::SongDescriptor { name: "House of Fire".to_string(), artist: "Dave Rodgers".to_string(), uri: None },
+        lyrics::SongDescriptor { name: "Let Me Go".to_string(), artist: "Mr. Foo".to_string(), uri: None },
+        lyrics::SongDescriptor { name: "Missing | Song".to_string(), artist: "Mr. Foo".to_string(), uri: None },
+    ]
+}
+
+fn write_to_string(report: &Report, format: OutputFormat) -> String {
+    let mut out = vec![];
+    write_report(&mut out, report, format).unwrap();
+
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn report_has_stable_json_schema() {
+    let songs = make_songs();
+    let report = Report::new(&make_word_counts(&songs), &[], &WordCountFilter::default());
+
+    let json = serde_json::from_str::<serde_json::Value>(&write_to_string(&report, OutputFormat::Json)).unwrap();
+
+    assert_eq!(json["version"], 1);
+    assert_eq!(json["stats"], serde_json::json!({ "songs": 3, "successes": 2, "failures": 1, "success_rate": 200f64 / 3f64 }));
+    assert_eq!(json["songs"][0]["song"]["name"], "House of Fire");
+    assert_eq!(json["songs"][0]["word_counts"], serde_json::json!([{ "word": "fire", "count": 3 }, { "word": "house", "count": 1 }]));
+    assert_eq!(json["aggregated"][0], serde_json::json!({ "word": "fire", "count": 4 }));
+    assert_eq!(json["failures"], serde_json::json!([{ "song": { "name": "Missing | Song", "artist": "Mr. Foo", "uri": null }, "reason": "Not found" }]));
+    assert_eq!(json["excluded"], serde_json::json!([]));
+}
+
+#[test]
+fn report_stats_handle_no_songs() {
+    let report = Report::new(&[], &[], &WordCountFilter::default());
+
+    assert_eq!(report.stats.songs, 0);
+    assert_eq!(report.stats.success_rate, 0f64);
+}
+
+#[test]
+fn failure_report_categorizes_failures() {
+    let songs = make_songs();
+    let failures = vec![
+        (songs[0].clone(), FetchError::not_found("Failed to fetch lyrics for House of Fire")),
+        (songs[1].clone(), FetchError::network("Failed to retrieve search content for song \"Let Me Go\": timed out")),
+        (songs[2].clone(), FetchError::not_found("Failed to fetch lyrics for Missing | Song")),
+    ];
+    let report = FailureReport::new(RunStats::new(4, 3), &failures);
+
+    let mut out = vec![];
+    write_failure_report(&mut out, &report).unwrap();
+    let json = serde_json::from_slice::<serde_json::Value>(&out).unwrap();
+
+    assert_eq!(json["version"], 1);
+    assert_eq!(json["stats"]["failures"], 3);
+    assert_eq!(json["categories"], serde_json::json!({ "network": 1, "not-found": 2 }));
+    assert_eq!(json["failures"][1]["song"]["name"], "Let Me Go");
+    assert_eq!(json["failures"][1]["category"], "network");
+}
+
+#[test]
+fn can_write_report_as_csv_and_tsv() {
+    let songs = make_songs();
+    let report = Report::new(&make_word_counts(&songs), &[&songs[1]], &WordCountFilter::default());
+
+    let csv = write_to_string(&report, OutputFormat::Csv);
+    let lines = csv.lines().collect::<Vec<_>>();
+
+    assert_eq!(lines[0], "kind,artist,song,word,count,reason");
+    assert_eq!(lines[1], "stat,,,songs,3,");
+    assert!(lines.contains(&"song,Dave Rodgers,House of Fire,fire,3,"));
+    assert!(lines.contains(&"total,,,fire,4,"));
+    assert!(lines.contains(&"failure,Mr. Foo,Missing | Song,,,Not found"));
+    assert!(lines.contains(&"excluded,Mr. Foo,Let Me Go,,,"));
+
+    let tsv = write_to_string(&report, OutputFormat::Tsv);
+
+    assert!(tsv.lines().any(|line| line == "song\tDave Rodgers\tHouse of Fire\tfire\t3\t"));
+}
+
+#[test]
+fn can_write_report_as_markdown() {
+    let songs = make_songs();
+    let markdown = write_to_string(&Report::new(&make_word_counts(&songs), &[], &WordCountFilter::default()), OutputFormat::Markdown);
+
+    assert!(markdown.contains("| 3 | 2 | 1 | 66.7% |"));
+    assert!(markdown.contains("## Most Common Words\n\n| Word | Count |\n| --- | ---: |\n| fire | 4 |"));
+    assert!(markdown.contains("## Dave Rodgers - House of Fire"));
+    assert!(markdown.contains("- Mr. Foo - Missing \\| Song: Not found"));
+}
+
+#[test]
+fn can_parse_output_formats() {
+    assert_eq!("JSON".parse::<OutputFormat>(), Ok(OutputFormat::Json));
+    assert_eq!("md".parse::<OutputFormat>(), Ok(OutputFormat::Markdown));
+    assert!("xml".parse::<OutputFormat>().is_err());
+}
+
+#[test]
+fn report_only_includes_filtered_words() {
+    let songs = make_songs();
+    let filter = WordCountFilterBuilder::default()
+        .sort_order(SortOrder::Alphabetical)
+        .min_count(2)
+        .build()
+        .unwrap();
+
+    let report = Report::new(&make_word_counts(&songs), &[], &filter);
+
+    assert_eq!(report.songs[0].word_counts, vec![WordCount { word: "fire".to_string(), count: 3 }]);
+    assert_eq!(report.aggregated, vec![
+        WordCount { word: "fire".to_string(), count: 4 },
+        WordCount { word: "go".to_string(), count: 2 },
+    ]);
+}
+
+#[test]
+fn can_write_word_counts_per_song() {
+    let songs = make_songs();
+    let filter = WordCountFilterBuilder::default().top(1).build().unwrap();
+
+    let mut out = vec![];
+    write_word_counts_for_songs(&mut out, make_word_counts(&songs), None, &filter, true).unwrap();
+    let text = String::from_utf8(out).unwrap();
+
+    assert!(text.contains("Most Common Words:\nfire: 4\n-------\n"));
+    assert!(text.contains("Words per Song:\nDave Rodgers - House of Fire\n\tfire: 3\nMr. Foo - Let Me Go\n\tgo: 2\n"));
+}